@@ -1,7 +1,16 @@
-use std::env;
-use std::path::PathBuf;
-
 fn main() {
+    // LibOVR only exists on Windows, other targets build without it
+    #[cfg(feature = "ovr")]
+    if std::env::var("CARGO_CFG_TARGET_OS").as_deref() == Ok("windows") {
+        libovr();
+    }
+}
+
+#[cfg(feature = "ovr")]
+fn libovr() {
+    use std::env;
+    use std::path::PathBuf;
+
     // This is the directory where the `c` library is located.
     let libdir_path = PathBuf::from("libovr")
         // Canonicalize the path as `rustc-link-search` requires an absolute
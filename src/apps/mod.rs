@@ -0,0 +1,127 @@
+use std::io;
+
+use crate::settings::Profile;
+
+#[cfg(target_os = "linux")]
+pub mod procfs;
+#[cfg(target_os = "windows")]
+pub mod toolhelp;
+
+// Executable names of running processes, and the one owning the focused window if known
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Apps {
+    pub running: Vec<String>,
+    pub foreground: Option<String>,
+}
+
+// Where profiles learn which apps are running, swappable so switching can be faked
+pub trait AppSource {
+    fn apps(&mut self) -> io::Result<Apps>;
+}
+
+#[cfg(target_os = "windows")]
+pub fn connect() -> Box<dyn AppSource> {
+    Box::new(toolhelp::Toolhelp)
+}
+
+#[cfg(target_os = "linux")]
+pub fn connect() -> Box<dyn AppSource> {
+    Box::new(procfs::Procfs)
+}
+
+// The foreground app picks first, otherwise the first profile with any app running. The
+// default profile never switches automatically
+pub fn detect(apps: &Apps, profiles: &[Profile]) -> Option<usize> {
+    let find = |matches: &dyn Fn(&Profile) -> bool| {
+        profiles
+            .iter()
+            .enumerate()
+            .skip(1)
+            .find(|(_, profile)| matches(profile))
+            .map(|(index, _)| index)
+    };
+
+    apps.foreground
+        .as_deref()
+        .and_then(|app| find(&|profile| profile.matches(app)))
+        .or_else(|| find(&|profile| apps.running.iter().any(|app| profile.matches(app))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Hands out one snapshot per poll
+    struct Fake(Vec<Apps>);
+
+    impl AppSource for Fake {
+        fn apps(&mut self) -> io::Result<Apps> {
+            match self.0.is_empty() {
+                true => Err(io::Error::other("No more snapshots")),
+                false => Ok(self.0.remove(0)),
+            }
+        }
+    }
+
+    fn apps(running: &[&str], foreground: Option<&str>) -> Apps {
+        Apps {
+            running: running.iter().map(|app| app.to_string()).collect(),
+            foreground: foreground.map(str::to_string),
+        }
+    }
+
+    fn profiles(apps: &[&[&str]]) -> Vec<Profile> {
+        apps.iter()
+            .map(|apps| Profile {
+                apps: apps.iter().map(|app| app.to_string()).collect(),
+                ..Profile::default()
+            })
+            .collect()
+    }
+
+    fn poll(source: &mut dyn AppSource, profiles: &[Profile]) -> Vec<Option<usize>> {
+        std::iter::from_fn(|| source.apps().ok())
+            .map(|apps| detect(&apps, profiles))
+            .collect()
+    }
+
+    #[test]
+    fn foreground_wins() {
+        let profiles = profiles(&[&[], &["VRChat.exe"], &["Discord.exe"]]);
+
+        let mut source = Fake(vec![
+            apps(&["VRChat.exe", "Discord.exe"], None),
+            apps(&["VRChat.exe", "Discord.exe"], Some("Discord.exe")),
+            apps(&["Discord.exe", "VRChat.exe"], Some("explorer.exe")),
+            apps(&["explorer.exe"], Some("explorer.exe")),
+        ]);
+
+        assert_eq!(
+            poll(&mut source, &profiles),
+            [Some(1), Some(2), Some(1), None]
+        );
+    }
+
+    #[test]
+    fn default_profile_never_switches() {
+        let profiles = profiles(&[&["VRChat.exe"], &["Discord.exe"]]);
+
+        let mut source = Fake(vec![
+            apps(&["VRChat.exe"], Some("VRChat.exe")),
+            apps(&["VRChat.exe", "Discord.exe"], Some("VRChat.exe")),
+        ]);
+
+        assert_eq!(poll(&mut source, &profiles), [None, Some(1)]);
+    }
+
+    #[test]
+    fn names_are_trimmed_and_ignore_case() {
+        let profiles = profiles(&[&[], &[" vrchat.EXE ", "  "]]);
+
+        assert_eq!(detect(&apps(&["VRChat.exe"], None), &profiles), Some(1));
+        assert_eq!(detect(&apps(&[], Some("VRCHAT.EXE")), &profiles), Some(1));
+        // Blank names don't match anything
+        assert_eq!(detect(&apps(&["", "  "], Some("")), &profiles), None);
+        assert_eq!(detect(&apps(&["VRChat"], None), &profiles), None);
+    }
+}
@@ -0,0 +1,42 @@
+use std::{fs, io};
+
+use super::{AppSource, Apps};
+
+// Reads process names from /proc, there is no portable way to find the focused window
+pub struct Procfs;
+
+impl AppSource for Procfs {
+    fn apps(&mut self) -> io::Result<Apps> {
+        let mut running = vec![];
+
+        for entry in fs::read_dir("/proc")? {
+            let path = entry?.path();
+
+            let is_pid = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| name.bytes().all(|c| c.is_ascii_digit()));
+
+            if !is_pid {
+                continue;
+            }
+
+            // The executable has the full name, `comm` is cut to 15 bytes but names games run
+            // through Wine by their .exe. Processes can exit while being listed
+            if let Ok(exe) = fs::read_link(path.join("exe")) {
+                if let Some(name) = exe.file_name() {
+                    running.push(name.to_string_lossy().into_owned());
+                }
+            }
+
+            if let Ok(comm) = fs::read_to_string(path.join("comm")) {
+                running.push(comm.trim().to_string());
+            }
+        }
+
+        Ok(Apps {
+            running,
+            foreground: None,
+        })
+    }
+}
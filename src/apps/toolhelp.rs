@@ -0,0 +1,56 @@
+use std::io;
+
+use windows::Win32::{
+    Foundation::CloseHandle,
+    System::Diagnostics::ToolHelp::{
+        CreateToolhelp32Snapshot, Process32FirstW, Process32NextW, PROCESSENTRY32W,
+        TH32CS_SNAPPROCESS,
+    },
+    UI::WindowsAndMessaging::{GetForegroundWindow, GetWindowThreadProcessId},
+};
+
+use super::{AppSource, Apps};
+
+// Lists processes from a Toolhelp snapshot and finds the foreground one by its process id
+pub struct Toolhelp;
+
+impl AppSource for Toolhelp {
+    fn apps(&mut self) -> io::Result<Apps> {
+        unsafe {
+            let mut foreground_id = 0;
+            GetWindowThreadProcessId(GetForegroundWindow(), Some(&mut foreground_id));
+
+            let snapshot = CreateToolhelp32Snapshot(TH32CS_SNAPPROCESS, 0)?;
+
+            let mut entry = PROCESSENTRY32W {
+                dwSize: std::mem::size_of::<PROCESSENTRY32W>() as u32,
+                ..Default::default()
+            };
+
+            let mut apps = Apps::default();
+            let mut next = Process32FirstW(snapshot, &mut entry);
+
+            while next.is_ok() {
+                let len = entry
+                    .szExeFile
+                    .iter()
+                    .position(|&c| c == 0)
+                    .unwrap_or(entry.szExeFile.len());
+                let name = String::from_utf16_lossy(&entry.szExeFile[..len]);
+
+                // Process id 0 is the idle process, also what no foreground window gives
+                if foreground_id != 0 && entry.th32ProcessID == foreground_id {
+                    apps.foreground = Some(name.clone());
+                }
+
+                apps.running.push(name);
+
+                next = Process32NextW(snapshot, &mut entry);
+            }
+
+            let _ = CloseHandle(snapshot);
+
+            Ok(apps)
+        }
+    }
+}
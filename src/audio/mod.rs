@@ -0,0 +1,138 @@
+use iced::{
+    futures::{channel::mpsc, SinkExt, Stream, StreamExt},
+    stream,
+};
+
+#[cfg(target_os = "linux")]
+pub mod pulse;
+#[cfg(target_os = "windows")]
+pub mod wasapi;
+
+#[derive(Debug, Clone)]
+pub struct AudioError {
+    pub code: i32,
+    pub reason: String,
+}
+
+pub type AudioResult<T = ()> = Result<T, AudioError>;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Microphone {
+    pub id: String,
+    pub name: String,
+    pub muted: bool,
+}
+
+// Capture device changes, microphones get listed again on any of them
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeviceEvent {
+    Added,
+    Removed,
+    StateChanged,
+    DefaultChanged,
+}
+
+// Mute change of a watched microphone, `ours` is whether Microwave made it when the platform can
+// tell
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MuteEvent {
+    pub muted: bool,
+    pub ours: Option<bool>,
+}
+
+// Input gain of a microphone in dB, within the range the device supports
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gain {
+    pub db: f32,
+    pub min: f32,
+    pub max: f32,
+}
+
+// Windows keeps separate default devices for general use and for voice chat
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    Console,
+    Communications,
+}
+
+// Capture device control, devices are addressed by their stable id
+pub trait AudioBackend {
+    fn microphones(&self) -> AudioResult<Vec<Microphone>>;
+
+    fn default_microphone(&self, role: Role) -> AudioResult<Option<String>>;
+
+    fn muted(&self, id: &str) -> AudioResult<bool>;
+
+    fn set_mute(&self, id: &str, mute: bool) -> AudioResult;
+
+    // Linear volume from 0.0 to 1.0
+    fn volume(&self, id: &str) -> AudioResult<f32>;
+
+    fn set_volume(&self, id: &str, volume: f32) -> AudioResult;
+
+    fn gain(&self, id: &str) -> AudioResult<Gain>;
+
+    // Clamped to the device's range
+    fn set_gain(&self, id: &str, db: f32) -> AudioResult;
+}
+
+#[cfg(target_os = "windows")]
+pub fn connect() -> AudioResult<Box<dyn AudioBackend>> {
+    Ok(Box::new(wasapi::Wasapi::new()?))
+}
+
+#[cfg(target_os = "linux")]
+pub fn connect() -> AudioResult<Box<dyn AudioBackend>> {
+    Ok(Box::new(pulse::Pulse::new()?))
+}
+
+// Device notifications arrive on a thread of their own, which stops once the stream is dropped
+pub fn watch() -> impl Stream<Item = DeviceEvent> {
+    stream::channel(16, |mut output| async move {
+        let (sender, mut receiver) = mpsc::unbounded();
+
+        #[cfg(target_os = "windows")]
+        std::thread::spawn(move || wasapi::watch(sender));
+        #[cfg(target_os = "linux")]
+        std::thread::spawn(move || pulse::watch(sender));
+
+        while let Some(event) = receiver.next().await {
+            let _ = output.send(event).await;
+        }
+    })
+}
+
+// Like `watch`, for the mute state of one microphone
+pub fn watch_mute(id: String) -> impl Stream<Item = MuteEvent> {
+    stream::channel(16, |mut output| async move {
+        let (sender, mut receiver) = mpsc::unbounded();
+
+        #[cfg(target_os = "windows")]
+        std::thread::spawn(move || wasapi::watch_mute(&id, sender));
+        #[cfg(target_os = "linux")]
+        std::thread::spawn(move || pulse::watch_mute(&id, sender));
+
+        while let Some(event) = receiver.next().await {
+            let _ = output.send(event).await;
+        }
+    })
+}
+
+// The first preferred id that's plugged in, then anything that looks like a headset, then the
+// default device
+pub fn pick_microphone(
+    mics: &[Microphone],
+    preferred: &[&str],
+    default: Option<&str>,
+) -> Option<Microphone> {
+    preferred
+        .iter()
+        .find_map(|&id| mics.iter().find(|mic| mic.id == id))
+        .or_else(|| {
+            mics.iter()
+                .find(|mic| mic.name.contains("Headset Microphone"))
+        })
+        .or_else(|| mics.iter().find(|mic| Some(mic.id.as_str()) == default))
+        .or_else(|| mics.first())
+        .cloned()
+}
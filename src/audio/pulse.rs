@@ -0,0 +1,264 @@
+use std::{
+    io::{BufRead, BufReader},
+    process::{Command, Stdio},
+    sync::mpsc::{self as std_mpsc, RecvTimeoutError},
+    thread,
+    time::Duration,
+};
+
+use iced::futures::channel::mpsc;
+
+use super::{
+    AudioBackend, AudioError, AudioResult, DeviceEvent, Gain, Microphone, MuteEvent, Role,
+};
+
+// Pulse volumes are software only, from silence up to the 150% pavucontrol allows
+const MIN_GAIN: f32 = -60.0;
+const MAX_GAIN: f32 = 10.5;
+
+// Raw volume at 100%
+const NORMAL_VOLUME: f32 = 65536.0;
+
+// How often watchers check whether their receiver is gone while nothing happens
+const CLOSED_CHECK: Duration = Duration::from_secs(1);
+
+// Talks to PulseAudio or pipewire-pulse through pactl, sources are addressed by name
+pub struct Pulse;
+
+impl Pulse {
+    pub fn new() -> AudioResult<Self> {
+        // Fails early if there is no server to talk to
+        pactl(&["info"])?;
+
+        Ok(Self)
+    }
+}
+
+impl AudioBackend for Pulse {
+    fn microphones(&self) -> AudioResult<Vec<Microphone>> {
+        let output = pactl(&["list", "sources"])?;
+
+        let mut sources: Vec<Vec<&str>> = vec![];
+
+        for line in output.lines() {
+            if line.starts_with("Source #") {
+                sources.push(vec![]);
+            } else if let Some(source) = sources.last_mut() {
+                source.push(line);
+            }
+        }
+
+        let mut inputs = vec![];
+
+        for source in sources {
+            let field = |key: &str| {
+                source.iter().find_map(|line| {
+                    line.strip_prefix('\t')?
+                        .strip_prefix(key)?
+                        .strip_prefix(": ")
+                        .map(str::trim)
+                })
+            };
+
+            // Monitors of output devices show up as sources too
+            if field("Monitor of Sink").is_some_and(|sink| sink != "n/a") {
+                continue;
+            }
+
+            let (Some(id), Some(name), Some(muted)) =
+                (field("Name"), field("Description"), field("Mute"))
+            else {
+                continue;
+            };
+
+            inputs.push(Microphone {
+                id: id.to_string(),
+                name: name.to_string(),
+                muted: muted == "yes",
+            });
+        }
+
+        Ok(inputs)
+    }
+
+    // Pulse has a single default source for every role
+    fn default_microphone(&self, _: Role) -> AudioResult<Option<String>> {
+        let output = pactl(&["info"])?;
+
+        Ok(output
+            .lines()
+            .find_map(|line| line.strip_prefix("Default Source: "))
+            .map(|source| source.trim().to_string()))
+    }
+
+    fn muted(&self, id: &str) -> AudioResult<bool> {
+        let output = pactl(&["get-source-mute", id])?;
+
+        Ok(output.trim() == "Mute: yes")
+    }
+
+    fn set_mute(&self, id: &str, mute: bool) -> AudioResult {
+        pactl(&["set-source-mute", id, if mute { "1" } else { "0" }])?;
+
+        Ok(())
+    }
+
+    fn volume(&self, id: &str) -> AudioResult<f32> {
+        Ok(source_volume(id, "%")? / 100.0)
+    }
+
+    fn set_volume(&self, id: &str, volume: f32) -> AudioResult {
+        let percent = format!("{}%", (volume.clamp(0.0, 1.0) * 100.0).round());
+
+        pactl(&["set-source-volume", id, &percent])?;
+
+        Ok(())
+    }
+
+    fn gain(&self, id: &str) -> AudioResult<Gain> {
+        Ok(Gain {
+            db: source_volume(id, " dB")?.clamp(MIN_GAIN, MAX_GAIN),
+            min: MIN_GAIN,
+            max: MAX_GAIN,
+        })
+    }
+
+    fn set_gain(&self, id: &str, db: f32) -> AudioResult {
+        // Pulse maps raw volume to dB with a cubic curve. A raw value also avoids pactl reading a
+        // negative "-6dB" as an option
+        let raw = NORMAL_VOLUME * 10f32.powf(db.clamp(MIN_GAIN, MAX_GAIN) / 60.0);
+
+        pactl(&["set-source-volume", id, &(raw.round() as u32).to_string()])?;
+
+        Ok(())
+    }
+}
+
+// Follows `pactl subscribe` until the receiver is gone, the default source is a server change
+pub fn watch(sender: mpsc::UnboundedSender<DeviceEvent>) {
+    subscribe(
+        || sender.is_closed(),
+        |line| {
+            // "Event 'new' on source #12". Changes on a source are mute and volume, which
+            // `watch_mute` already follows, while a card changes when a jack is plugged in
+            let event = match (
+                line.contains("'new'"),
+                line.contains("'remove'"),
+                line.contains(" on source #"),
+                line.contains("'change' on card #"),
+                line.contains(" on server"),
+            ) {
+                (true, _, true, _, _) => DeviceEvent::Added,
+                (_, true, true, _, _) => DeviceEvent::Removed,
+                (_, _, _, true, _) => DeviceEvent::StateChanged,
+                (_, _, _, _, true) => DeviceEvent::DefaultChanged,
+                _ => return true,
+            };
+
+            sender.unbounded_send(event).is_ok()
+        },
+    );
+}
+
+// Checks the source on every source change, pulse doesn't say which client made a change
+pub fn watch_mute(id: &str, sender: mpsc::UnboundedSender<MuteEvent>) {
+    let mut last = Pulse.muted(id).ok();
+
+    subscribe(
+        || sender.is_closed(),
+        |line| {
+            if !line.contains("'change' on source #") {
+                return true;
+            }
+
+            // Volume changes come through here too
+            let Ok(muted) = Pulse.muted(id) else {
+                return true;
+            };
+
+            if last == Some(muted) {
+                return true;
+            }
+
+            last = Some(muted);
+
+            sender
+                .unbounded_send(MuteEvent { muted, ours: None })
+                .is_ok()
+        },
+    );
+}
+
+// Hands `pactl subscribe` lines to `event` until it returns false or `closed` does, which is
+// checked while no events come so the process is stopped soon after the receiver is dropped
+fn subscribe(closed: impl Fn() -> bool, mut event: impl FnMut(&str) -> bool) {
+    let Ok(mut child) = Command::new("pactl")
+        .arg("subscribe")
+        .env("LC_ALL", "C")
+        .stdout(Stdio::piped())
+        .spawn()
+    else {
+        return;
+    };
+
+    if let Some(stdout) = child.stdout.take() {
+        let (sender, receiver) = std_mpsc::channel();
+
+        // Ends once the process is killed and its output closes
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        loop {
+            match receiver.recv_timeout(CLOSED_CHECK) {
+                Ok(line) if !event(&line) => break,
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) if closed() => break,
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+        }
+    }
+
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// The first level of a source in `unit`, either "%" or " dB"
+fn source_volume(id: &str, unit: &str) -> AudioResult<f32> {
+    let output = pactl(&["get-source-volume", id])?;
+
+    // "Volume: front-left: 65536 /  100% / 0.00 dB,   front-right: ...", channels are treated as
+    // one so the first level is enough. Silence is "-inf dB"
+    output
+        .split(['/', ','])
+        .find_map(|part| part.trim().strip_suffix(unit)?.parse::<f32>().ok())
+        .ok_or(AudioError {
+            code: 0,
+            reason: format!("Unexpected pactl volume output: {}", output.trim()),
+        })
+}
+
+fn pactl(args: &[&str]) -> AudioResult<String> {
+    let output = Command::new("pactl")
+        .args(args)
+        .env("LC_ALL", "C")
+        .output()
+        .map_err(|error| AudioError {
+            code: error.raw_os_error().unwrap_or_default(),
+            reason: format!("Unable to run pactl: {error}"),
+        })?;
+
+    if !output.status.success() {
+        return Err(AudioError {
+            code: output.status.code().unwrap_or_default(),
+            reason: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        });
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
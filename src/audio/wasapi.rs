@@ -0,0 +1,277 @@
+use std::{thread, time::Duration};
+
+use iced::futures::channel::mpsc;
+use windows::{
+    core::{implement, GUID, HSTRING, PCWSTR},
+    Win32::{
+        Devices::Properties,
+        Foundation::ERROR_NOT_FOUND,
+        Media::Audio::{
+            eCapture, eCommunications, eConsole, EDataFlow, ERole,
+            Endpoints::{
+                IAudioEndpointVolume, IAudioEndpointVolumeCallback,
+                IAudioEndpointVolumeCallback_Impl,
+            },
+            IMMDeviceEnumerator, IMMNotificationClient, IMMNotificationClient_Impl,
+            MMDeviceEnumerator, AUDIO_VOLUME_NOTIFICATION_DATA, DEVICE_STATE, DEVICE_STATE_ACTIVE,
+        },
+        System::Com::{
+            CoCreateInstance, CoInitializeEx, CoUninitialize, CLSCTX_ALL, COINIT_MULTITHREADED,
+            STGM_READ,
+        },
+        UI::Shell::PropertiesSystem::PROPERTYKEY,
+    },
+};
+
+use super::{
+    AudioBackend, AudioError, AudioResult, DeviceEvent, Gain, Microphone, MuteEvent, Role,
+};
+
+// Event context of our own volume and mute changes, volume callbacks pass it back
+const CONTEXT: GUID = GUID::from_u128(0x5f0b_2c1e_7a4d_4e8b_9c36_d1a2_b3c4_e5f6);
+
+pub struct Wasapi {
+    enumerator: IMMDeviceEnumerator,
+}
+
+impl Wasapi {
+    pub fn new() -> AudioResult<Self> {
+        let enumerator = unsafe {
+            CoCreateInstance::<_, IMMDeviceEnumerator>(&MMDeviceEnumerator, None, CLSCTX_ALL)?
+        };
+
+        Ok(Self { enumerator })
+    }
+
+    unsafe fn endpoint(&self, id: &str) -> windows::core::Result<IAudioEndpointVolume> {
+        self.enumerator
+            .GetDevice(&HSTRING::from(id))?
+            .Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)
+    }
+
+    // Lowest and highest level in dB, the increment isn't needed
+    unsafe fn range(endpoint: &IAudioEndpointVolume) -> windows::core::Result<(f32, f32)> {
+        let (mut min, mut max, mut increment) = (0.0, 0.0, 0.0);
+
+        endpoint.GetVolumeRange(&mut min, &mut max, &mut increment)?;
+
+        Ok((min, max))
+    }
+}
+
+impl AudioBackend for Wasapi {
+    fn microphones(&self) -> AudioResult<Vec<Microphone>> {
+        unsafe {
+            let collection = self
+                .enumerator
+                .EnumAudioEndpoints(eCapture, DEVICE_STATE_ACTIVE)?;
+
+            let mut inputs = vec![];
+
+            for i in 0..collection.GetCount()? {
+                let device = collection.Item(i)?;
+                let volume = device.Activate::<IAudioEndpointVolume>(CLSCTX_ALL, None)?;
+
+                inputs.push(Microphone {
+                    id: device.GetId()?.to_string()?,
+                    name: device
+                        .OpenPropertyStore(STGM_READ)?
+                        .GetValue(&PROPERTYKEY {
+                            fmtid: Properties::DEVPKEY_Device_FriendlyName.fmtid,
+                            pid: Properties::DEVPKEY_Device_FriendlyName.pid,
+                        })?
+                        .to_string(),
+                    muted: volume.GetMute()?.as_bool(),
+                });
+            }
+
+            Ok(inputs)
+        }
+    }
+
+    fn default_microphone(&self, role: Role) -> AudioResult<Option<String>> {
+        let role = match role {
+            Role::Console => eConsole,
+            Role::Communications => eCommunications,
+        };
+
+        unsafe {
+            match self.enumerator.GetDefaultAudioEndpoint(eCapture, role) {
+                Ok(device) => Ok(Some(device.GetId()?.to_string()?)),
+                // No capture devices at all
+                Err(error) if error.code() == ERROR_NOT_FOUND.to_hresult() => Ok(None),
+                Err(error) => Err(error.into()),
+            }
+        }
+    }
+
+    fn muted(&self, id: &str) -> AudioResult<bool> {
+        unsafe { Ok(self.endpoint(id)?.GetMute()?.as_bool()) }
+    }
+
+    fn set_mute(&self, id: &str, mute: bool) -> AudioResult {
+        unsafe { Ok(self.endpoint(id)?.SetMute(mute, &CONTEXT)?) }
+    }
+
+    fn volume(&self, id: &str) -> AudioResult<f32> {
+        unsafe { Ok(self.endpoint(id)?.GetMasterVolumeLevelScalar()?) }
+    }
+
+    fn set_volume(&self, id: &str, volume: f32) -> AudioResult {
+        unsafe {
+            Ok(self
+                .endpoint(id)?
+                .SetMasterVolumeLevelScalar(volume.clamp(0.0, 1.0), &CONTEXT)?)
+        }
+    }
+
+    fn gain(&self, id: &str) -> AudioResult<Gain> {
+        unsafe {
+            let endpoint = self.endpoint(id)?;
+            let (min, max) = Self::range(&endpoint)?;
+
+            Ok(Gain {
+                db: endpoint.GetMasterVolumeLevel()?,
+                min,
+                max,
+            })
+        }
+    }
+
+    fn set_gain(&self, id: &str, db: f32) -> AudioResult {
+        unsafe {
+            let endpoint = self.endpoint(id)?;
+            let (min, max) = Self::range(&endpoint)?;
+
+            Ok(endpoint.SetMasterVolumeLevel(db.clamp(min, max), &CONTEXT)?)
+        }
+    }
+}
+
+#[implement(IMMNotificationClient)]
+struct Notifications {
+    sender: mpsc::UnboundedSender<DeviceEvent>,
+}
+
+impl IMMNotificationClient_Impl for Notifications_Impl {
+    fn OnDeviceStateChanged(&self, _: &PCWSTR, _: DEVICE_STATE) -> windows::core::Result<()> {
+        let _ = self.sender.unbounded_send(DeviceEvent::StateChanged);
+        Ok(())
+    }
+
+    fn OnDeviceAdded(&self, _: &PCWSTR) -> windows::core::Result<()> {
+        let _ = self.sender.unbounded_send(DeviceEvent::Added);
+        Ok(())
+    }
+
+    fn OnDeviceRemoved(&self, _: &PCWSTR) -> windows::core::Result<()> {
+        let _ = self.sender.unbounded_send(DeviceEvent::Removed);
+        Ok(())
+    }
+
+    fn OnDefaultDeviceChanged(
+        &self,
+        flow: EDataFlow,
+        _: ERole,
+        _: &PCWSTR,
+    ) -> windows::core::Result<()> {
+        if flow == eCapture {
+            let _ = self.sender.unbounded_send(DeviceEvent::DefaultChanged);
+        }
+
+        Ok(())
+    }
+
+    fn OnPropertyValueChanged(&self, _: &PCWSTR, _: &PROPERTYKEY) -> windows::core::Result<()> {
+        Ok(())
+    }
+}
+
+// Notifications come in on threads of the audio service, this one only keeps the callback
+// registered until the receiver is gone
+pub fn watch(sender: mpsc::UnboundedSender<DeviceEvent>) {
+    unsafe {
+        if CoInitializeEx(None, COINIT_MULTITHREADED).is_err() {
+            return;
+        }
+
+        if let Ok(enumerator) =
+            CoCreateInstance::<_, IMMDeviceEnumerator>(&MMDeviceEnumerator, None, CLSCTX_ALL)
+        {
+            let client: IMMNotificationClient = Notifications {
+                sender: sender.clone(),
+            }
+            .into();
+
+            if enumerator
+                .RegisterEndpointNotificationCallback(&client)
+                .is_ok()
+            {
+                while !sender.is_closed() {
+                    thread::sleep(Duration::from_secs(1));
+                }
+
+                let _ = enumerator.UnregisterEndpointNotificationCallback(&client);
+            }
+        }
+
+        CoUninitialize();
+    }
+}
+
+#[implement(IAudioEndpointVolumeCallback)]
+struct VolumeNotifications {
+    sender: mpsc::UnboundedSender<MuteEvent>,
+}
+
+impl IAudioEndpointVolumeCallback_Impl for VolumeNotifications_Impl {
+    fn OnNotify(&self, notify: *mut AUDIO_VOLUME_NOTIFICATION_DATA) -> windows::core::Result<()> {
+        if let Some(data) = unsafe { notify.as_ref() } {
+            let _ = self.sender.unbounded_send(MuteEvent {
+                muted: data.bMuted.as_bool(),
+                ours: Some(data.guidEventContext == CONTEXT),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+// Same as `watch`, for the volume callbacks of one endpoint
+pub fn watch_mute(id: &str, sender: mpsc::UnboundedSender<MuteEvent>) {
+    unsafe {
+        if CoInitializeEx(None, COINIT_MULTITHREADED).is_err() {
+            return;
+        }
+
+        let endpoint = Wasapi::new()
+            .ok()
+            .and_then(|wasapi| wasapi.endpoint(id).ok());
+
+        if let Some(endpoint) = endpoint {
+            let callback: IAudioEndpointVolumeCallback = VolumeNotifications {
+                sender: sender.clone(),
+            }
+            .into();
+
+            if endpoint.RegisterControlChangeNotify(&callback).is_ok() {
+                while !sender.is_closed() {
+                    thread::sleep(Duration::from_secs(1));
+                }
+
+                let _ = endpoint.UnregisterControlChangeNotify(&callback);
+            }
+        }
+
+        CoUninitialize();
+    }
+}
+
+impl From<windows::core::Error> for AudioError {
+    fn from(error: windows::core::Error) -> Self {
+        Self {
+            code: error.code().0,
+            reason: error.message(),
+        }
+    }
+}
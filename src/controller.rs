@@ -0,0 +1,1382 @@
+use std::{fmt, str::FromStr};
+
+use serde::{Deserialize, Serialize};
+
+use crate::input::{
+    InputState, BUTTON_A, BUTTON_B, BUTTON_BACK, BUTTON_DOWN, BUTTON_ENTER, BUTTON_LEFT,
+    BUTTON_L_SHOULDER, BUTTON_L_THUMB, BUTTON_RIGHT, BUTTON_R_SHOULDER, BUTTON_R_THUMB, BUTTON_UP,
+    BUTTON_X, BUTTON_Y, TOUCH_A, TOUCH_B, TOUCH_L_INDEX_POINTING, TOUCH_L_INDEX_TRIGGER,
+    TOUCH_L_THUMB, TOUCH_L_THUMB_REST, TOUCH_L_THUMB_UP, TOUCH_R_INDEX_POINTING,
+    TOUCH_R_INDEX_TRIGGER, TOUCH_R_THUMB, TOUCH_R_THUMB_REST, TOUCH_R_THUMB_UP, TOUCH_X, TOUCH_Y,
+};
+
+// Holding the last chord this long while capturing makes it a long press
+const CAPTURE_LONG_PRESS: f64 = 0.5;
+// Capturing gives up if nothing is pressed for this long
+const CAPTURE_TIMEOUT: f64 = 10.0;
+// Time a binding waits for the rest of a longer one that contains it
+const CHORD_GRACE: f64 = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Chord {
+    pub button: u32,
+    pub trigger: u8,
+    pub stick: u8,
+    // Capacitive touches that have to be touched and not touched
+    pub touch: u32,
+    pub untouch: u32,
+}
+
+// A capacitive touch and whether it has to be touched, picked from the UI since touches can't
+// be captured by pressing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TouchCondition {
+    pub touch: u32,
+    pub touched: bool,
+}
+
+// Chords pressed in order, double taps are the same chord twice. Stored as text such as
+// `LThumb+RThumb` or `A,A;window=0.3`, see `FromStr`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Binding {
+    pub steps: Vec<Chord>,
+    // Seconds the last chord has to be held before the binding is pressed
+    pub hold: f64,
+    // Seconds allowed between the steps of a sequence
+    pub window: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindingError {
+    UnknownInput(String),
+    UnknownOption(String),
+    InvalidValue(String),
+    EmptyStep,
+}
+
+// `index` is the binding the conflict is reported for and `other` the binding it collides with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Conflict {
+    pub index: usize,
+    pub other: usize,
+    pub kind: ConflictKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictKind {
+    // Identical to an earlier binding, which wins
+    Same,
+    // Part of a longer binding, which wins while it's pressed
+    Subsumed,
+    // Shares inputs with another binding, both fire when held together
+    Overlap,
+    // Same inputs as a binding with a longer hold, fires on release if let go before that
+    Tap,
+}
+
+// Triggers count as pressed above `press` until they drop below `release`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct TriggerThreshold {
+    pub press: f32,
+    pub release: f32,
+}
+
+#[derive(Debug)]
+pub struct Controller {
+    pub bindings: Vec<Binding>,
+    // Left index, right index, left hand and right hand
+    pub trigger_thresholds: [TriggerThreshold; 4],
+    // Stick distance a direction counts from, and the angle each direction covers in degrees
+    pub stick_deadzone: f32,
+    pub stick_sector: f32,
+    matchers: Vec<Matcher>,
+    // Binding indices with the ones that win conflicts first, and the bindings each one loses to
+    order: Vec<usize>,
+    shadowed_by: Vec<Vec<usize>>,
+    // Bindings on the same inputs with a longer hold, which a binding waits on until release
+    deferred_by: Vec<Vec<usize>>,
+    capture: Option<Capture>,
+    prev: Chord,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Matcher {
+    // Next step to match and when the previous one was pressed
+    step: usize,
+    last: f64,
+    // Time the last step was pressed, waiting on the hold time
+    rise: Option<f64>,
+    // Time the binding was pressed at
+    pressed: Option<f64>,
+}
+
+#[derive(Debug)]
+struct Capture {
+    index: usize,
+    // Put back when the capture is cancelled, None when the binding was added for it
+    previous: Option<Binding>,
+    // Poll time of the first update, captures start outside of updates
+    started_at: Option<f64>,
+    binding: Binding,
+    chord: Chord,
+    pressed_at: Option<f64>,
+    // Last time the chord grew, holds count from when it was complete
+    completed_at: f64,
+    // Press of the last finished step
+    last_press: Option<f64>,
+}
+
+// Bindings are addressed by their index in `Controller::bindings`
+#[derive(Debug, Clone)]
+pub enum ControllerEvent {
+    Pressed(usize),
+    // Seconds the binding was held for
+    Released(usize, f64),
+    BindingUpdate(usize, Binding),
+    BindingSet(usize, Binding),
+    // Capture was cancelled or timed out, the previous binding is back in place
+    BindingCancelled(usize),
+}
+
+impl Chord {
+    pub const EMPTY: Self = Self {
+        button: 0,
+        trigger: 0,
+        stick: 0,
+        touch: 0,
+        untouch: 0,
+    };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::EMPTY
+    }
+
+    fn contains(&self, other: Chord) -> bool {
+        self.button & other.button == other.button
+            && self.trigger & other.trigger == other.trigger
+            && self.stick & other.stick == other.stick
+            && self.touch & other.touch == other.touch
+            && self.untouch & other.untouch == other.untouch
+    }
+
+    fn len(&self) -> u32 {
+        self.button.count_ones()
+            + self.trigger.count_ones()
+            + self.stick.count_ones()
+            + self.touch.count_ones()
+            + self.untouch.count_ones()
+    }
+
+    fn intersects(&self, other: Chord) -> bool {
+        self.button & other.button != 0
+            || self.trigger & other.trigger != 0
+            || self.stick & other.stick != 0
+    }
+
+    fn union(self, other: Chord) -> Chord {
+        Chord {
+            button: self.button | other.button,
+            trigger: self.trigger | other.trigger,
+            stick: self.stick | other.stick,
+            touch: self.touch | other.touch,
+            untouch: self.untouch | other.untouch,
+        }
+    }
+
+    fn toggle(&mut self, condition: TouchCondition) {
+        let (set, other) = if condition.touched {
+            (&mut self.touch, &mut self.untouch)
+        } else {
+            (&mut self.untouch, &mut self.touch)
+        };
+
+        *set ^= condition.touch;
+        *other &= !condition.touch;
+    }
+}
+
+impl TouchCondition {
+    pub fn all() -> Vec<TouchCondition> {
+        TOUCH_MAPPINGS
+            .iter()
+            .flat_map(|&(touch, _)| [true, false].map(|touched| TouchCondition { touch, touched }))
+            .collect()
+    }
+}
+
+impl Default for TriggerThreshold {
+    fn default() -> Self {
+        Self {
+            press: 0.85,
+            release: 0.75,
+        }
+    }
+}
+
+impl Default for Binding {
+    fn default() -> Self {
+        Self {
+            steps: vec![Chord {
+                button: BUTTON_L_THUMB | BUTTON_R_THUMB,
+                ..Chord::EMPTY
+            }],
+            hold: 0.0,
+            window: 0.5,
+        }
+    }
+}
+
+impl Binding {
+    pub fn empty() -> Self {
+        Self {
+            steps: vec![],
+            ..Self::default()
+        }
+    }
+
+    pub fn is_sequence(&self) -> bool {
+        self.steps.len() > 1
+    }
+
+    // Longer sequences win, then bigger last chords
+    fn precedence(&self) -> (usize, u32) {
+        (self.steps.len(), self.steps.last().map_or(0, Chord::len))
+    }
+
+    // Touch conditions apply to the last step
+    pub fn toggle_touch(&mut self, condition: TouchCondition) {
+        if self.steps.is_empty() {
+            self.steps.push(Chord::EMPTY);
+        }
+
+        if let Some(chord) = self.steps.last_mut() {
+            chord.toggle(condition);
+        }
+
+        if self.steps.last().is_some_and(Chord::is_empty) {
+            self.steps.pop();
+        }
+    }
+
+    pub fn has_touches(&self) -> bool {
+        self.steps
+            .last()
+            .is_some_and(|chord| chord.touch | chord.untouch != 0)
+    }
+
+    // Drops everything that has to be pressed, leaving the last step's touch conditions
+    pub fn keep_touches(&mut self) {
+        let touches = self.steps.last().map(|chord| Chord {
+            touch: chord.touch,
+            untouch: chord.untouch,
+            ..Chord::EMPTY
+        });
+
+        self.steps = touches
+            .filter(|chord| !chord.is_empty())
+            .into_iter()
+            .collect();
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let triggers = TRIGGER_MAPPINGS
+            .iter()
+            .filter(|(trigger, _)| trigger & self.trigger != 0)
+            .map(|(_, string)| *string);
+
+        let buttons = BUTTON_MAPPINGS
+            .iter()
+            .filter(|(button, _)| button & self.button != 0)
+            .map(|(_, string)| *string);
+
+        let sticks = STICK_MAPPINGS
+            .iter()
+            .filter(|(stick, _)| stick & self.stick != 0)
+            .map(|(_, string)| *string);
+
+        let touches = TOUCH_MAPPINGS
+            .iter()
+            .filter(|(touch, _)| touch & (self.touch | self.untouch) != 0)
+            .map(|&(touch, _)| TouchCondition {
+                touch,
+                touched: touch & self.touch != 0,
+            });
+
+        let names = triggers
+            .chain(buttons)
+            .chain(sticks)
+            .map(str::to_string)
+            .chain(touches.map(|condition| condition.to_string()));
+
+        for (i, name) in names.enumerate() {
+            if i != 0 {
+                f.write_str(" + ")?;
+            }
+
+            f.write_str(&name)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for TouchCondition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = TOUCH_MAPPINGS
+            .iter()
+            .find(|(touch, _)| *touch == self.touch)
+            .map_or("Unknown", |(_, string)| string);
+
+        if self.touched {
+            f.write_str(name)
+        } else {
+            write!(f, "Not {name}")
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, chord) in self.steps.iter().enumerate() {
+            if i != 0 {
+                f.write_str(" then ")?;
+            }
+
+            write!(f, "{chord}")?;
+        }
+
+        if self.hold > 0.0 {
+            write!(f, " (hold {:.1}s)", self.hold)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Chord {
+    // Inputs joined by `+`, touch conditions that have to be untouched start with `!`
+    fn to_text(self) -> String {
+        let triggers = TRIGGER_MAPPINGS
+            .iter()
+            .filter(|(trigger, _)| trigger & self.trigger != 0)
+            .map(|(_, string)| token(string));
+
+        let buttons = BUTTON_MAPPINGS
+            .iter()
+            .filter(|(button, _)| button & self.button != 0)
+            .map(|(_, string)| token(string));
+
+        let sticks = STICK_MAPPINGS
+            .iter()
+            .filter(|(stick, _)| stick & self.stick != 0)
+            .map(|(_, string)| token(string));
+
+        let touches = TOUCH_MAPPINGS
+            .iter()
+            .filter(|(touch, _)| touch & (self.touch | self.untouch) != 0)
+            .map(|&(touch, string)| {
+                if touch & self.touch != 0 {
+                    token(string)
+                } else {
+                    format!("!{}", token(string))
+                }
+            });
+
+        triggers
+            .chain(buttons)
+            .chain(sticks)
+            .chain(touches)
+            .collect::<Vec<_>>()
+            .join("+")
+    }
+}
+
+impl FromStr for Chord {
+    type Err = BindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chord = Chord::EMPTY;
+
+        for input in s.split('+').map(str::trim) {
+            if input.is_empty() {
+                return Err(BindingError::EmptyStep);
+            }
+
+            if let Some(touch) = input.strip_prefix('!') {
+                chord.untouch |= find(TOUCH_MAPPINGS, touch.trim())
+                    .ok_or_else(|| BindingError::UnknownInput(input.to_string()))?;
+            } else if let Some(trigger) = find(TRIGGER_MAPPINGS, input) {
+                chord.trigger |= trigger;
+            } else if let Some(button) = find(BUTTON_MAPPINGS, input) {
+                chord.button |= button;
+            } else if let Some(stick) = find(STICK_MAPPINGS, input) {
+                chord.stick |= stick;
+            } else if let Some(touch) = find(TOUCH_MAPPINGS, input) {
+                chord.touch |= touch;
+            } else {
+                return Err(BindingError::UnknownInput(input.to_string()));
+            }
+        }
+
+        // A touch can't have to be touched and untouched at once
+        if chord.touch & chord.untouch != 0 {
+            return Err(BindingError::InvalidValue(s.trim().to_string()));
+        }
+
+        Ok(chord)
+    }
+}
+
+impl Binding {
+    // Steps joined by `,`, followed by `;hold=` and `;window=` when they differ from the default
+    pub fn to_text(&self) -> String {
+        let mut text = self
+            .steps
+            .iter()
+            .map(|chord| chord.to_text())
+            .collect::<Vec<_>>()
+            .join(",");
+
+        let default = Binding::default();
+
+        if self.hold != default.hold {
+            text += &format!(";hold={}", self.hold);
+        }
+
+        if self.window != default.window {
+            text += &format!(";window={}", self.window);
+        }
+
+        text
+    }
+}
+
+impl FromStr for Binding {
+    type Err = BindingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(';');
+        let steps = parts.next().unwrap_or_default().trim();
+
+        let mut binding = Binding {
+            steps: if steps.is_empty() {
+                vec![]
+            } else {
+                steps
+                    .split(',')
+                    .map(str::parse::<Chord>)
+                    .collect::<Result<_, _>>()?
+            },
+            ..Binding::default()
+        };
+
+        for option in parts {
+            let (key, value) = option
+                .split_once('=')
+                .ok_or_else(|| BindingError::UnknownOption(option.trim().to_string()))?;
+
+            let seconds = value
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|seconds| seconds.is_finite() && *seconds >= 0.0)
+                .ok_or_else(|| BindingError::InvalidValue(option.trim().to_string()))?;
+
+            match key.trim() {
+                "hold" => binding.hold = seconds,
+                "window" => binding.window = seconds,
+                key => return Err(BindingError::UnknownOption(key.to_string())),
+            }
+        }
+
+        Ok(binding)
+    }
+}
+
+impl TryFrom<String> for Binding {
+    type Error = BindingError;
+
+    fn try_from(text: String) -> Result<Self, Self::Error> {
+        text.parse()
+    }
+}
+
+impl From<Binding> for String {
+    fn from(binding: Binding) -> Self {
+        binding.to_text()
+    }
+}
+
+impl fmt::Display for BindingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BindingError::UnknownInput(input) => write!(f, "Unknown input \"{input}\""),
+            BindingError::UnknownOption(option) => write!(f, "Unknown option \"{option}\""),
+            BindingError::InvalidValue(value) => write!(f, "Invalid value \"{value}\""),
+            BindingError::EmptyStep => f.write_str("Empty step"),
+        }
+    }
+}
+
+impl Default for Controller {
+    fn default() -> Self {
+        Self {
+            bindings: vec![Binding::default()],
+            trigger_thresholds: [TriggerThreshold::default(); 4],
+            stick_deadzone: 0.8,
+            stick_sector: 90.0,
+            matchers: vec![Matcher::default()],
+            order: vec![0],
+            shadowed_by: vec![vec![]],
+            deferred_by: vec![vec![]],
+            capture: None,
+            prev: Chord::EMPTY,
+        }
+    }
+}
+
+impl Controller {
+    pub fn update(&mut self, state: &InputState) -> Vec<ControllerEvent> {
+        let triggers = [
+            (L_INDEX_TRIGGER, &state.index_trigger[0]),
+            (R_INDEX_TRIGGER, &state.index_trigger[1]),
+            (L_HAND_TRIGGER, &state.hand_trigger[0]),
+            (R_HAND_TRIGGER, &state.hand_trigger[1]),
+        ];
+
+        let mut trigger_state = 0;
+
+        for ((val, &trigger), threshold) in triggers.into_iter().zip(self.trigger_thresholds) {
+            let threshold = if self.prev.trigger & val != 0 {
+                threshold.release
+            } else {
+                threshold.press
+            };
+
+            if trigger > threshold {
+                trigger_state |= val;
+            }
+        }
+
+        let mut stick_state = 0;
+
+        for (&[x, y], directions) in state.thumbstick.iter().zip(STICK_DIRECTIONS) {
+            if x.hypot(y) < self.stick_deadzone {
+                continue;
+            }
+
+            let angle = y.atan2(x).to_degrees();
+
+            for (val, center) in directions {
+                // Wraps the difference into -180..180
+                let difference = (angle - center + 540.0) % 360.0 - 180.0;
+
+                if difference.abs() <= self.stick_sector / 2.0 {
+                    stick_state |= val;
+                }
+            }
+        }
+
+        let current = Chord {
+            button: state.buttons,
+            trigger: trigger_state,
+            stick: stick_state,
+            touch: state.touches,
+            untouch: !state.touches,
+        };
+
+        let prev = std::mem::replace(&mut self.prev, current);
+
+        if self.capture.is_some() {
+            let pressed = Chord {
+                button: current.button,
+                trigger: current.trigger,
+                stick: current.stick,
+                ..Chord::EMPTY
+            };
+
+            return self.capture(pressed, state.time).into_iter().collect();
+        }
+
+        // Touches change all the time and don't break sequences
+        let newly = Chord {
+            button: current.button & !prev.button,
+            trigger: current.trigger & !prev.trigger,
+            stick: current.stick & !prev.stick,
+            ..Chord::EMPTY
+        };
+
+        let rose =
+            |chord: Chord| !chord.is_empty() && current.contains(chord) && !prev.contains(chord);
+
+        let mut events = vec![];
+
+        for &index in &self.order {
+            let binding = &self.bindings[index];
+
+            let Some(&last) = binding.steps.last() else {
+                continue;
+            };
+
+            let shadowed = !self.shadowed_by[index].is_empty();
+            let losing = self.shadowed_by[index]
+                .iter()
+                .any(|&other| self.matchers[other].rise.is_some());
+
+            let deferred = !self.deferred_by[index].is_empty();
+            let outheld = self.deferred_by[index]
+                .iter()
+                .any(|&other| self.matchers[other].pressed.is_some());
+
+            let matcher = &mut self.matchers[index];
+
+            if matcher.rise.is_none() {
+                if matcher.step > 0 && state.time - matcher.last > binding.window {
+                    matcher.step = 0;
+                }
+
+                let step = binding.steps[matcher.step];
+
+                if rose(step) {
+                    matcher.step += 1;
+                    matcher.last = state.time;
+                } else if !step.contains(newly) {
+                    // Pressing anything else breaks a sequence
+                    matcher.step = usize::from(rose(binding.steps[0]));
+                    matcher.last = state.time;
+                }
+
+                if matcher.step == binding.steps.len() {
+                    matcher.step = 0;
+                    matcher.rise = Some(state.time);
+                }
+            }
+
+            let Some(rise) = matcher.rise else {
+                continue;
+            };
+
+            // Shadowed bindings give the rest of a longer chord a moment to be pressed
+            let hold = if shadowed {
+                binding.hold.max(CHORD_GRACE)
+            } else {
+                binding.hold
+            };
+
+            if current.contains(last) {
+                if matcher.pressed.is_none() && (losing || outheld) {
+                    matcher.rise = None;
+                } else if matcher.pressed.is_none() && !deferred && state.time - rise >= hold {
+                    matcher.pressed = Some(state.time);
+
+                    events.push(ControllerEvent::Pressed(index));
+                }
+            } else {
+                matcher.rise = None;
+
+                if let Some(time) = matcher.pressed.take() {
+                    events.push(ControllerEvent::Released(index, state.time - time));
+                } else if deferred && !outheld && state.time - rise >= hold {
+                    // Let go before the longer hold fired, so it was this binding after all
+                    events.push(ControllerEvent::Pressed(index));
+                    events.push(ControllerEvent::Released(index, state.time - rise));
+                }
+            }
+        }
+
+        events
+    }
+
+    fn capture(&mut self, current: Chord, time: f64) -> Option<ControllerEvent> {
+        let capture = self.capture.as_mut()?;
+        let started_at = *capture.started_at.get_or_insert(time);
+
+        if capture.binding.steps.is_empty()
+            && capture.pressed_at.is_none()
+            && current.is_empty()
+            && time - started_at >= CAPTURE_TIMEOUT
+        {
+            return self.cancel_setting_binding();
+        }
+
+        if capture.pressed_at.is_none() && !current.is_empty() {
+            capture.pressed_at = Some(time);
+            capture.chord = Chord::EMPTY;
+        }
+
+        if let Some(pressed_at) = capture.pressed_at {
+            let chord = capture.chord.union(current);
+
+            if chord != capture.chord {
+                capture.chord = chord;
+                capture.completed_at = time;
+
+                let mut binding = capture.binding.clone();
+                binding.steps.push(chord);
+
+                return Some(ControllerEvent::BindingUpdate(capture.index, binding));
+            }
+
+            if !current.intersects(capture.chord) {
+                let held = time - capture.completed_at;
+
+                capture.binding.steps.push(capture.chord);
+                capture.binding.hold = if held >= CAPTURE_LONG_PRESS {
+                    (held * 10.0).floor() / 10.0
+                } else {
+                    0.0
+                };
+
+                capture.pressed_at = None;
+                capture.last_press = Some(pressed_at);
+            }
+
+            return None;
+        }
+
+        // Finished once another step could no longer follow
+        let last_press = capture.last_press?;
+
+        if time - last_press <= capture.binding.window {
+            return None;
+        }
+
+        let capture = self.capture.take()?;
+        self.bindings[capture.index] = capture.binding.clone();
+        self.resolve_conflicts();
+
+        Some(ControllerEvent::BindingSet(capture.index, capture.binding))
+    }
+
+    // Setting the binding one past the end adds a new one
+    pub fn start_setting_binding(&mut self, index: usize) {
+        let added = index == self.bindings.len();
+
+        if added {
+            self.bindings.push(Binding::empty());
+            self.matchers.push(Matcher::default());
+        }
+
+        let Some(binding) = self.bindings.get_mut(index) else {
+            return;
+        };
+
+        let previous = std::mem::replace(binding, Binding::empty());
+        self.matchers[index] = Matcher::default();
+        self.resolve_conflicts();
+
+        self.capture = Some(Capture {
+            index,
+            previous: (!added).then_some(previous),
+            started_at: None,
+            binding: Binding::empty(),
+            chord: Chord::EMPTY,
+            pressed_at: None,
+            completed_at: 0.0,
+            last_press: None,
+        });
+    }
+
+    pub fn cancel_setting_binding(&mut self) -> Option<ControllerEvent> {
+        let capture = self.capture.take()?;
+
+        match capture.previous {
+            Some(previous) => {
+                self.bindings[capture.index] = previous;
+                self.matchers[capture.index] = Matcher::default();
+            }
+            None => {
+                self.bindings.remove(capture.index);
+                self.matchers.remove(capture.index);
+            }
+        }
+
+        self.resolve_conflicts();
+
+        Some(ControllerEvent::BindingCancelled(capture.index))
+    }
+
+    // A capture in progress keeps going, the new binding in its place is what cancelling puts back
+    pub fn set_bindings(&mut self, mut bindings: Vec<Binding>) {
+        match &mut self.capture {
+            Some(capture) if capture.index < bindings.len() => {
+                let binding = std::mem::replace(&mut bindings[capture.index], Binding::empty());
+                capture.previous = Some(binding);
+            }
+            Some(capture) if capture.index == bindings.len() => {
+                bindings.push(Binding::empty());
+                capture.previous = None;
+            }
+            _ => self.capture = None,
+        }
+
+        self.matchers = vec![Matcher::default(); bindings.len()];
+        self.bindings = bindings;
+        self.resolve_conflicts();
+    }
+
+    fn resolve_conflicts(&mut self) {
+        self.shadowed_by = vec![vec![]; self.bindings.len()];
+        self.deferred_by = vec![vec![]; self.bindings.len()];
+
+        for conflict in conflicts(&self.bindings) {
+            match conflict.kind {
+                ConflictKind::Same | ConflictKind::Subsumed => {
+                    self.shadowed_by[conflict.index].push(conflict.other);
+                }
+                ConflictKind::Tap => self.deferred_by[conflict.index].push(conflict.other),
+                ConflictKind::Overlap => {}
+            }
+        }
+
+        // Stable, so earlier bindings still come first between equals
+        self.order = (0..self.bindings.len()).collect();
+        self.order
+            .sort_by_key(|&index| std::cmp::Reverse(self.bindings[index].precedence()));
+    }
+}
+
+// Longest binding wins: when the last chord of one binding is part of another's, the one with
+// more steps or the bigger chord suppresses it, and identical bindings go to the earlier one.
+// Bindings that only differ in hold time are split by how long the inputs are held
+pub fn conflicts(bindings: &[Binding]) -> Vec<Conflict> {
+    let mut conflicts = vec![];
+
+    for (index, binding) in bindings.iter().enumerate() {
+        let Some(&last) = binding.steps.last() else {
+            continue;
+        };
+
+        for (other, other_binding) in bindings.iter().enumerate() {
+            let Some(&other_last) = other_binding.steps.last() else {
+                continue;
+            };
+
+            if other == index {
+                continue;
+            }
+
+            let kind = if binding.steps == other_binding.steps {
+                if binding.hold < other_binding.hold {
+                    Some(ConflictKind::Tap)
+                } else {
+                    (binding.hold == other_binding.hold && other < index)
+                        .then_some(ConflictKind::Same)
+                }
+            } else if other_last.contains(last) && other_binding.precedence() > binding.precedence()
+            {
+                Some(ConflictKind::Subsumed)
+            } else if last.intersects(other_last)
+                && !other_last.contains(last)
+                && !last.contains(other_last)
+            {
+                Some(ConflictKind::Overlap)
+            } else {
+                None
+            };
+
+            if let Some(kind) = kind {
+                conflicts.push(Conflict { index, other, kind });
+            }
+        }
+    }
+
+    conflicts
+}
+
+// Text tokens are the display names without spaces, matched case insensitively
+fn token(name: &str) -> String {
+    name.replace(' ', "")
+}
+
+fn find<T: Copy>(mappings: &[(T, &str)], input: &str) -> Option<T> {
+    mappings
+        .iter()
+        .find(|(_, name)| token(name).eq_ignore_ascii_case(input))
+        .map(|&(val, _)| val)
+}
+
+const BUTTON_MAPPINGS: &[(u32, &str)] = &[
+    (BUTTON_A, "A"),
+    (BUTTON_B, "B"),
+    (BUTTON_X, "X"),
+    (BUTTON_Y, "Y"),
+    (BUTTON_L_THUMB, "L Thumb"),
+    (BUTTON_R_THUMB, "R Thumb"),
+    (BUTTON_L_SHOULDER, "L Shoulder"),
+    (BUTTON_R_SHOULDER, "R Shoulder"),
+    (BUTTON_UP, "Up"),
+    (BUTTON_DOWN, "Down"),
+    (BUTTON_LEFT, "Left"),
+    (BUTTON_RIGHT, "Right"),
+    (BUTTON_ENTER, "Menu"),
+    (BUTTON_BACK, "Back"),
+];
+
+const L_INDEX_TRIGGER: u8 = 1 << 0;
+const R_INDEX_TRIGGER: u8 = 1 << 1;
+const L_HAND_TRIGGER: u8 = 1 << 2;
+const R_HAND_TRIGGER: u8 = 1 << 3;
+
+const TRIGGER_MAPPINGS: &[(u8, &str)] = &[
+    (L_INDEX_TRIGGER, "L Index Trigger"),
+    (R_INDEX_TRIGGER, "R Index Trigger"),
+    (L_HAND_TRIGGER, "L Hand Trigger"),
+    (R_HAND_TRIGGER, "R Hand Trigger"),
+];
+
+const L_STICK_RIGHT: u8 = 1 << 0;
+const L_STICK_UP: u8 = 1 << 1;
+const L_STICK_LEFT: u8 = 1 << 2;
+const L_STICK_DOWN: u8 = 1 << 3;
+const R_STICK_RIGHT: u8 = 1 << 4;
+const R_STICK_UP: u8 = 1 << 5;
+const R_STICK_LEFT: u8 = 1 << 6;
+const R_STICK_DOWN: u8 = 1 << 7;
+
+// Direction bits and their angles in degrees for the left and right stick
+const STICK_DIRECTIONS: [[(u8, f32); 4]; 2] = [
+    [
+        (L_STICK_RIGHT, 0.0),
+        (L_STICK_UP, 90.0),
+        (L_STICK_LEFT, 180.0),
+        (L_STICK_DOWN, -90.0),
+    ],
+    [
+        (R_STICK_RIGHT, 0.0),
+        (R_STICK_UP, 90.0),
+        (R_STICK_LEFT, 180.0),
+        (R_STICK_DOWN, -90.0),
+    ],
+];
+
+const STICK_MAPPINGS: &[(u8, &str)] = &[
+    (L_STICK_UP, "L Stick Up"),
+    (L_STICK_DOWN, "L Stick Down"),
+    (L_STICK_LEFT, "L Stick Left"),
+    (L_STICK_RIGHT, "L Stick Right"),
+    (R_STICK_UP, "R Stick Up"),
+    (R_STICK_DOWN, "R Stick Down"),
+    (R_STICK_LEFT, "R Stick Left"),
+    (R_STICK_RIGHT, "R Stick Right"),
+];
+
+const TOUCH_MAPPINGS: &[(u32, &str)] = &[
+    (TOUCH_A, "A Touch"),
+    (TOUCH_B, "B Touch"),
+    (TOUCH_X, "X Touch"),
+    (TOUCH_Y, "Y Touch"),
+    (TOUCH_L_THUMB, "L Thumb Touch"),
+    (TOUCH_R_THUMB, "R Thumb Touch"),
+    (TOUCH_L_THUMB_REST, "L Thumb Rest"),
+    (TOUCH_R_THUMB_REST, "R Thumb Rest"),
+    (TOUCH_L_INDEX_TRIGGER, "L Index Touch"),
+    (TOUCH_R_INDEX_TRIGGER, "R Index Touch"),
+    (TOUCH_L_INDEX_POINTING, "L Index Pointing"),
+    (TOUCH_R_INDEX_POINTING, "R Index Pointing"),
+    (TOUCH_L_THUMB_UP, "L Thumb Up"),
+    (TOUCH_R_THUMB_UP, "R Thumb Up"),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::{sim::Sim, InputBackend};
+
+    fn parse(text: &str) -> Binding {
+        text.parse().unwrap()
+    }
+
+    fn controller(bindings: &[&str]) -> Controller {
+        let mut controller = Controller::default();
+        controller.set_bindings(bindings.iter().map(|text| parse(text)).collect());
+        controller
+    }
+
+    // Plays a sim script at 100 Hz for `seconds`, events are written out with times rounded to
+    // a tenth of a second
+    fn run(controller: &mut Controller, script: &str, seconds: f64) -> Vec<String> {
+        let mut sim = Sim::parse(script).unwrap().with_refresh_rate(100.0);
+        let mut events = vec![];
+
+        loop {
+            let state = sim.poll().unwrap();
+
+            if state.time > seconds {
+                return events;
+            }
+
+            events.extend(
+                controller
+                    .update(&state)
+                    .into_iter()
+                    .map(|event| match event {
+                        ControllerEvent::Pressed(index) => format!("Pressed({index})"),
+                        ControllerEvent::Released(index, held) => {
+                            format!("Released({index}, {held:.1})")
+                        }
+                        ControllerEvent::BindingUpdate(index, binding) => {
+                            format!("BindingUpdate({index}, {})", binding.to_text())
+                        }
+                        ControllerEvent::BindingSet(index, binding) => {
+                            format!("BindingSet({index}, {})", binding.to_text())
+                        }
+                        ControllerEvent::BindingCancelled(index) => {
+                            format!("BindingCancelled({index})")
+                        }
+                    }),
+            );
+        }
+    }
+
+    #[test]
+    fn chord_press_and_release() {
+        let mut controller = controller(&["LThumb+RThumb"]);
+
+        let script = "
+            0.1 400
+            0.2 404
+            0.6 400
+            0.7 0
+        ";
+
+        assert_eq!(
+            run(&mut controller, script, 1.0),
+            ["Pressed(0)", "Released(0, 0.4)"]
+        );
+    }
+
+    #[test]
+    fn double_tap_within_window() {
+        let mut controller = controller(&["A,A"]);
+
+        let script = "
+            0.1 1
+            0.2 0
+            0.3 1
+            0.4 0
+            # Too slow
+            1.0 1
+            1.1 0
+            1.8 1
+            1.9 0
+            # Broken up by another button
+            3.0 1
+            3.1 2
+            3.2 1
+            3.3 0
+        ";
+
+        assert_eq!(
+            run(&mut controller, script, 4.0),
+            ["Pressed(0)", "Released(0, 0.1)"]
+        );
+    }
+
+    #[test]
+    fn shorter_chord_waits_for_longer() {
+        let mut controller = controller(&["A", "A+B"]);
+
+        let script = "
+            0.1 1
+            0.15 3
+            0.55 0
+            1.0 1
+            1.5 0
+        ";
+
+        assert_eq!(
+            run(&mut controller, script, 2.0),
+            [
+                "Pressed(1)",
+                "Released(1, 0.4)",
+                "Pressed(0)",
+                "Released(0, 0.4)"
+            ]
+        );
+    }
+
+    #[test]
+    fn trigger_hysteresis() {
+        let mut controller = controller(&["LIndexTrigger"]);
+
+        // Dips between the release and press thresholds don't release
+        let script = "
+            0.1 0 0 0.9
+            0.2 0 0 0.8
+            0.3 0 0 0.9
+            0.4 0 0 0.7
+            0.5 0 0 0.8
+        ";
+
+        assert_eq!(
+            run(&mut controller, script, 1.0),
+            ["Pressed(0)", "Released(0, 0.3)"]
+        );
+    }
+
+    #[test]
+    fn tap_and_long_press_split() {
+        let mut split = controller(&["A", "A;hold=1"]);
+
+        let script = "
+            0.1 1
+            2.1 0
+            3.0 1
+            3.2 0
+        ";
+
+        assert_eq!(
+            run(&mut split, script, 4.0),
+            [
+                "Pressed(1)",
+                "Released(1, 1.0)",
+                "Pressed(0)",
+                "Released(0, 0.2)"
+            ]
+        );
+
+        // Let go on the poll right after the long press fires
+        let mut late = controller(&["A", "A;hold=1"]);
+
+        assert_eq!(
+            run(&mut late, "0.1 1\n1.115 0", 2.0),
+            ["Pressed(1)", "Released(1, 0.0)"]
+        );
+    }
+
+    #[test]
+    fn touch_only_binding() {
+        let mut controller = controller(&["!RThumbRest"]);
+
+        let script = format!(
+            "
+            0 0 {TOUCH_R_THUMB_REST:x}
+            0.1 0 0
+            0.5 0 {TOUCH_R_THUMB_REST:x}
+            "
+        );
+
+        assert_eq!(
+            run(&mut controller, &script, 1.0),
+            ["Pressed(0)", "Released(0, 0.4)"]
+        );
+    }
+
+    #[test]
+    fn capture_chord() {
+        let mut controller = controller(&["A"]);
+        controller.start_setting_binding(0);
+
+        // The hold counts from when R Thumb completed the chord
+        let script = "
+            0.1 400
+            0.55 404
+            0.8 0
+        ";
+
+        assert_eq!(
+            run(&mut controller, script, 2.0),
+            [
+                "BindingUpdate(0, LThumb)",
+                "BindingUpdate(0, LThumb+RThumb)",
+                "BindingSet(0, LThumb+RThumb)"
+            ]
+        );
+        assert_eq!(controller.bindings[0], parse("LThumb+RThumb"));
+    }
+
+    #[test]
+    fn capture_long_press_and_sequence() {
+        let mut controller = controller(&["A"]);
+        controller.start_setting_binding(0);
+
+        let script = "
+            0.1 2
+            0.2 0
+            0.3 2
+            1.15 0
+        ";
+
+        assert_eq!(
+            run(&mut controller, script, 3.0),
+            [
+                "BindingUpdate(0, B)",
+                "BindingUpdate(0, B,B)",
+                "BindingSet(0, B,B;hold=0.8)"
+            ]
+        );
+    }
+
+    #[test]
+    fn capture_survives_binding_edits() {
+        let mut controller = controller(&["A", "B"]);
+        controller.start_setting_binding(2);
+
+        // Another binding's hold is changed while capturing
+        controller.set_bindings(vec![parse("A"), parse("B;hold=1")]);
+
+        assert_eq!(
+            run(&mut controller, "0.1 100\n0.2 0", 1.0),
+            ["BindingUpdate(2, X)", "BindingSet(2, X)"]
+        );
+        assert_eq!(
+            controller.bindings,
+            [parse("A"), parse("B;hold=1"), parse("X")]
+        );
+
+        controller.start_setting_binding(0);
+        controller.set_bindings(vec![parse("Y"), parse("B")]);
+        controller.cancel_setting_binding();
+
+        assert_eq!(controller.bindings, [parse("Y"), parse("B")]);
+    }
+
+    #[test]
+    fn capture_times_out() {
+        let mut controller = controller(&["A"]);
+        controller.start_setting_binding(0);
+
+        assert_eq!(
+            run(&mut controller, "", CAPTURE_TIMEOUT + 1.0),
+            ["BindingCancelled(0)"]
+        );
+        assert_eq!(controller.bindings, [parse("A")]);
+    }
+
+    #[test]
+    fn capture_cancel_restores() {
+        let mut controller = controller(&["A"]);
+        controller.start_setting_binding(0);
+
+        assert_eq!(run(&mut controller, "0.1 2", 0.5), ["BindingUpdate(0, B)"]);
+
+        assert!(matches!(
+            controller.cancel_setting_binding(),
+            Some(ControllerEvent::BindingCancelled(0))
+        ));
+        assert_eq!(controller.bindings, [parse("A")]);
+
+        // Added bindings are removed again
+        controller.start_setting_binding(1);
+        controller.cancel_setting_binding();
+
+        assert_eq!(controller.bindings, [parse("A")]);
+        assert_eq!(
+            run(&mut controller, "0.1 1\n0.2 0", 0.5),
+            ["Pressed(0)", "Released(0, 0.1)"]
+        );
+    }
+
+    #[test]
+    fn text_round_trips() {
+        for text in [
+            "LThumb+RThumb",
+            "A,A;window=0.3",
+            "A;hold=1",
+            "LIndexTrigger+!RThumbRest",
+            "!RThumbRest",
+            "RHandTrigger+B+LStickUp,Back;hold=0.5;window=0.2",
+            "",
+        ] {
+            assert_eq!(parse(text).to_text(), text);
+        }
+    }
+
+    #[test]
+    fn every_input_round_trips() {
+        let chord = Chord {
+            button: BUTTON_MAPPINGS
+                .iter()
+                .fold(0, |mask, (button, _)| mask | button),
+            trigger: TRIGGER_MAPPINGS
+                .iter()
+                .fold(0, |mask, (trigger, _)| mask | trigger),
+            stick: STICK_MAPPINGS
+                .iter()
+                .fold(0, |mask, (stick, _)| mask | stick),
+            touch: TOUCH_A | TOUCH_L_THUMB_REST,
+            untouch: TOUCH_B | TOUCH_R_INDEX_POINTING,
+        };
+
+        let binding = Binding {
+            steps: vec![
+                chord,
+                Chord {
+                    button: BUTTON_A,
+                    ..Chord::EMPTY
+                },
+            ],
+            hold: 0.25,
+            window: 1.5,
+        };
+
+        assert_eq!(parse(&binding.to_text()), binding);
+    }
+
+    #[test]
+    fn text_ignores_case_and_spaces() {
+        assert_eq!(
+            parse(" lthumb + rthumb ; hold = 0.5 "),
+            parse("LThumb+RThumb;hold=0.5")
+        );
+    }
+
+    #[test]
+    fn serde_uses_text() {
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct File {
+            binding: Binding,
+        }
+
+        let file = File {
+            binding: parse("A,A;window=0.3"),
+        };
+
+        let text = toml::to_string(&file).unwrap();
+
+        assert_eq!(text.trim(), "binding = \"A,A;window=0.3\"");
+        assert_eq!(toml::from_str::<File>(&text).unwrap(), file);
+    }
+
+    #[test]
+    fn text_errors() {
+        let error = |text: &str| text.parse::<Binding>().unwrap_err();
+
+        assert_eq!(
+            error("LThumb+Typo"),
+            BindingError::UnknownInput("Typo".to_string())
+        );
+        // Only touches can have to be untouched
+        assert_eq!(error("!A"), BindingError::UnknownInput("!A".to_string()));
+        assert_eq!(error("A,,B"), BindingError::EmptyStep);
+        assert_eq!(error("A+"), BindingError::EmptyStep);
+        assert_eq!(
+            error("ATouch+!ATouch"),
+            BindingError::InvalidValue("ATouch+!ATouch".to_string())
+        );
+        assert_eq!(
+            error("A;hold=x"),
+            BindingError::InvalidValue("hold=x".to_string())
+        );
+        assert_eq!(
+            error("A;hold=-1"),
+            BindingError::InvalidValue("hold=-1".to_string())
+        );
+        assert_eq!(
+            error("A;window=inf"),
+            BindingError::InvalidValue("window=inf".to_string())
+        );
+        assert_eq!(
+            error("A;speed=1"),
+            BindingError::UnknownOption("speed".to_string())
+        );
+        assert_eq!(
+            error("A;hold"),
+            BindingError::UnknownOption("hold".to_string())
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use std::io;
+
+#[cfg(feature = "openxr")]
+pub mod openxr;
+#[cfg(all(target_os = "windows", feature = "ovr"))]
+pub mod ovr;
+pub mod record;
+pub mod replay;
+pub mod sim;
+
+#[derive(Debug, Clone)]
+pub struct InputError {
+    pub code: i32,
+    pub reason: String,
+}
+
+pub type InputResult<T = ()> = Result<T, InputError>;
+
+impl From<io::Error> for InputError {
+    fn from(error: io::Error) -> Self {
+        Self {
+            code: error.raw_os_error().unwrap_or_default(),
+            reason: error.to_string(),
+        }
+    }
+}
+
+// Raw controller state for a single poll, button and touch bits follow the LibOVR layout
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct InputState {
+    pub time: f64,
+    pub buttons: u32,
+    pub touches: u32,
+    pub index_trigger: [f32; 2],
+    pub hand_trigger: [f32; 2],
+    pub thumbstick: [[f32; 2]; 2],
+}
+
+// Used when no other backend is asked for
+#[cfg(all(target_os = "windows", feature = "ovr"))]
+pub type DefaultBackend = ovr::Ovr;
+#[cfg(all(not(all(target_os = "windows", feature = "ovr")), feature = "openxr"))]
+pub type DefaultBackend = openxr::OpenXr;
+#[cfg(not(any(all(target_os = "windows", feature = "ovr"), feature = "openxr")))]
+pub type DefaultBackend = sim::Sim;
+
+pub trait InputBackend: Send {
+    fn connect() -> InputResult<Self>
+    where
+        Self: Sized;
+
+    fn headset(&self) -> &str;
+
+    fn refresh_rate(&self) -> f32;
+
+    fn poll(&mut self) -> InputResult<InputState>;
+
+    fn shutdown(&mut self);
+}
+
+pub const BUTTON_A: u32 = 0x0000_0001;
+pub const BUTTON_B: u32 = 0x0000_0002;
+pub const BUTTON_R_THUMB: u32 = 0x0000_0004;
+pub const BUTTON_R_SHOULDER: u32 = 0x0000_0008;
+pub const BUTTON_X: u32 = 0x0000_0100;
+pub const BUTTON_Y: u32 = 0x0000_0200;
+pub const BUTTON_L_THUMB: u32 = 0x0000_0400;
+pub const BUTTON_L_SHOULDER: u32 = 0x0000_0800;
+// D-pad on the Xbox controller, directional buttons on the Remote
+pub const BUTTON_UP: u32 = 0x0001_0000;
+pub const BUTTON_DOWN: u32 = 0x0002_0000;
+pub const BUTTON_LEFT: u32 = 0x0004_0000;
+pub const BUTTON_RIGHT: u32 = 0x0008_0000;
+pub const BUTTON_ENTER: u32 = 0x0010_0000;
+pub const BUTTON_BACK: u32 = 0x0020_0000;
+
+pub const TOUCH_A: u32 = 0x0000_0001;
+pub const TOUCH_B: u32 = 0x0000_0002;
+pub const TOUCH_R_THUMB: u32 = 0x0000_0004;
+pub const TOUCH_R_THUMB_REST: u32 = 0x0000_0008;
+pub const TOUCH_R_INDEX_TRIGGER: u32 = 0x0000_0010;
+pub const TOUCH_R_INDEX_POINTING: u32 = 0x0000_0020;
+pub const TOUCH_R_THUMB_UP: u32 = 0x0000_0040;
+pub const TOUCH_X: u32 = 0x0000_0100;
+pub const TOUCH_Y: u32 = 0x0000_0200;
+pub const TOUCH_L_THUMB: u32 = 0x0000_0400;
+pub const TOUCH_L_THUMB_REST: u32 = 0x0000_0800;
+pub const TOUCH_L_INDEX_TRIGGER: u32 = 0x0000_1000;
+pub const TOUCH_L_INDEX_POINTING: u32 = 0x0000_2000;
+pub const TOUCH_L_THUMB_UP: u32 = 0x0000_4000;
@@ -0,0 +1,936 @@
+mod bindings {
+    #![allow(non_camel_case_types)]
+    #![allow(non_snake_case)]
+
+    use std::ffi::{c_char, c_void};
+
+    pub type XrResult = i32;
+    pub type XrStructureType = i32;
+    pub type XrBool32 = u32;
+    pub type XrTime = i64;
+    pub type XrPath = u64;
+    pub type XrSystemId = u64;
+    pub type XrInstance = u64;
+    pub type XrSession = u64;
+    pub type XrActionSet = u64;
+    pub type XrAction = u64;
+
+    pub const XR_TYPE_EXTENSION_PROPERTIES: XrStructureType = 2;
+    pub const XR_TYPE_INSTANCE_CREATE_INFO: XrStructureType = 3;
+    pub const XR_TYPE_SYSTEM_GET_INFO: XrStructureType = 4;
+    pub const XR_TYPE_SYSTEM_PROPERTIES: XrStructureType = 5;
+    pub const XR_TYPE_SESSION_CREATE_INFO: XrStructureType = 8;
+    pub const XR_TYPE_SESSION_BEGIN_INFO: XrStructureType = 10;
+    pub const XR_TYPE_EVENT_DATA_BUFFER: XrStructureType = 16;
+    pub const XR_TYPE_EVENT_DATA_INSTANCE_LOSS_PENDING: XrStructureType = 17;
+    pub const XR_TYPE_EVENT_DATA_SESSION_STATE_CHANGED: XrStructureType = 18;
+    pub const XR_TYPE_ACTION_STATE_BOOLEAN: XrStructureType = 23;
+    pub const XR_TYPE_ACTION_STATE_FLOAT: XrStructureType = 24;
+    pub const XR_TYPE_ACTION_STATE_VECTOR2F: XrStructureType = 25;
+    pub const XR_TYPE_ACTION_SET_CREATE_INFO: XrStructureType = 28;
+    pub const XR_TYPE_ACTION_CREATE_INFO: XrStructureType = 29;
+    pub const XR_TYPE_INTERACTION_PROFILE_SUGGESTED_BINDING: XrStructureType = 51;
+    pub const XR_TYPE_ACTION_STATE_GET_INFO: XrStructureType = 58;
+    pub const XR_TYPE_SESSION_ACTION_SETS_ATTACH_INFO: XrStructureType = 60;
+    pub const XR_TYPE_ACTIONS_SYNC_INFO: XrStructureType = 61;
+
+    pub const XR_EVENT_UNAVAILABLE: XrResult = 4;
+
+    pub const XR_CURRENT_API_VERSION: u64 = 1 << 48;
+    pub const XR_FORM_FACTOR_HEAD_MOUNTED_DISPLAY: i32 = 1;
+    pub const XR_VIEW_CONFIGURATION_TYPE_PRIMARY_STEREO: i32 = 2;
+
+    pub const XR_ACTION_TYPE_BOOLEAN_INPUT: i32 = 1;
+    pub const XR_ACTION_TYPE_FLOAT_INPUT: i32 = 2;
+    pub const XR_ACTION_TYPE_VECTOR2F_INPUT: i32 = 3;
+
+    pub const XR_SESSION_STATE_READY: i32 = 2;
+    pub const XR_SESSION_STATE_STOPPING: i32 = 6;
+    pub const XR_SESSION_STATE_LOSS_PENDING: i32 = 7;
+    pub const XR_SESSION_STATE_EXITING: i32 = 8;
+
+    pub const XR_MND_HEADLESS_EXTENSION_NAME: &str = "XR_MND_headless";
+
+    #[repr(C)]
+    pub struct XrApplicationInfo {
+        pub applicationName: [c_char; 128],
+        pub applicationVersion: u32,
+        pub engineName: [c_char; 128],
+        pub engineVersion: u32,
+        pub apiVersion: u64,
+    }
+
+    #[repr(C)]
+    pub struct XrInstanceCreateInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub createFlags: u64,
+        pub applicationInfo: XrApplicationInfo,
+        pub enabledApiLayerCount: u32,
+        pub enabledApiLayerNames: *const *const c_char,
+        pub enabledExtensionCount: u32,
+        pub enabledExtensionNames: *const *const c_char,
+    }
+
+    #[repr(C)]
+    pub struct XrExtensionProperties {
+        pub ty: XrStructureType,
+        pub next: *mut c_void,
+        pub extensionName: [c_char; 128],
+        pub extensionVersion: u32,
+    }
+
+    #[repr(C)]
+    pub struct XrSystemGetInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub formFactor: i32,
+    }
+
+    #[repr(C)]
+    pub struct XrSystemProperties {
+        pub ty: XrStructureType,
+        pub next: *mut c_void,
+        pub systemId: XrSystemId,
+        pub vendorId: u32,
+        pub systemName: [c_char; 256],
+        pub graphicsProperties: [u32; 3],
+        pub trackingProperties: [XrBool32; 2],
+    }
+
+    #[repr(C)]
+    pub struct XrSessionCreateInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub createFlags: u64,
+        pub systemId: XrSystemId,
+    }
+
+    #[repr(C)]
+    pub struct XrSessionBeginInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub primaryViewConfigurationType: i32,
+    }
+
+    #[repr(C)]
+    pub struct XrEventDataBuffer {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub varying: [u8; 4000],
+    }
+
+    #[repr(C)]
+    pub struct XrEventDataSessionStateChanged {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub session: XrSession,
+        pub state: i32,
+        pub time: XrTime,
+    }
+
+    #[repr(C)]
+    pub struct XrActionSetCreateInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub actionSetName: [c_char; 64],
+        pub localizedActionSetName: [c_char; 128],
+        pub priority: u32,
+    }
+
+    #[repr(C)]
+    pub struct XrActionCreateInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub actionName: [c_char; 64],
+        pub actionType: i32,
+        pub countSubactionPaths: u32,
+        pub subactionPaths: *const XrPath,
+        pub localizedActionName: [c_char; 128],
+    }
+
+    #[repr(C)]
+    pub struct XrActionSuggestedBinding {
+        pub action: XrAction,
+        pub binding: XrPath,
+    }
+
+    #[repr(C)]
+    pub struct XrInteractionProfileSuggestedBinding {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub interactionProfile: XrPath,
+        pub countSuggestedBindings: u32,
+        pub suggestedBindings: *const XrActionSuggestedBinding,
+    }
+
+    #[repr(C)]
+    pub struct XrSessionActionSetsAttachInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub countActionSets: u32,
+        pub actionSets: *const XrActionSet,
+    }
+
+    #[repr(C)]
+    pub struct XrActiveActionSet {
+        pub actionSet: XrActionSet,
+        pub subactionPath: XrPath,
+    }
+
+    #[repr(C)]
+    pub struct XrActionsSyncInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub countActiveActionSets: u32,
+        pub activeActionSets: *const XrActiveActionSet,
+    }
+
+    #[repr(C)]
+    pub struct XrActionStateGetInfo {
+        pub ty: XrStructureType,
+        pub next: *const c_void,
+        pub action: XrAction,
+        pub subactionPath: XrPath,
+    }
+
+    #[repr(C)]
+    pub struct XrActionStateBoolean {
+        pub ty: XrStructureType,
+        pub next: *mut c_void,
+        pub currentState: XrBool32,
+        pub changedSinceLastSync: XrBool32,
+        pub lastChangeTime: XrTime,
+        pub isActive: XrBool32,
+    }
+
+    #[repr(C)]
+    pub struct XrActionStateFloat {
+        pub ty: XrStructureType,
+        pub next: *mut c_void,
+        pub currentState: f32,
+        pub changedSinceLastSync: XrBool32,
+        pub lastChangeTime: XrTime,
+        pub isActive: XrBool32,
+    }
+
+    #[repr(C)]
+    pub struct XrActionStateVector2f {
+        pub ty: XrStructureType,
+        pub next: *mut c_void,
+        pub currentState: [f32; 2],
+        pub changedSinceLastSync: XrBool32,
+        pub lastChangeTime: XrTime,
+        pub isActive: XrBool32,
+    }
+
+    #[link(name = "openxr_loader")]
+    extern "system" {
+        pub fn xrEnumerateInstanceExtensionProperties(
+            layerName: *const c_char,
+            propertyCapacityInput: u32,
+            propertyCountOutput: *mut u32,
+            properties: *mut XrExtensionProperties,
+        ) -> XrResult;
+        pub fn xrCreateInstance(
+            createInfo: *const XrInstanceCreateInfo,
+            instance: *mut XrInstance,
+        ) -> XrResult;
+        pub fn xrDestroyInstance(instance: XrInstance) -> XrResult;
+        pub fn xrResultToString(
+            instance: XrInstance,
+            value: XrResult,
+            buffer: *mut [c_char; 64],
+        ) -> XrResult;
+        pub fn xrGetSystem(
+            instance: XrInstance,
+            getInfo: *const XrSystemGetInfo,
+            systemId: *mut XrSystemId,
+        ) -> XrResult;
+        pub fn xrGetSystemProperties(
+            instance: XrInstance,
+            systemId: XrSystemId,
+            properties: *mut XrSystemProperties,
+        ) -> XrResult;
+        pub fn xrCreateSession(
+            instance: XrInstance,
+            createInfo: *const XrSessionCreateInfo,
+            session: *mut XrSession,
+        ) -> XrResult;
+        pub fn xrBeginSession(session: XrSession, beginInfo: *const XrSessionBeginInfo)
+            -> XrResult;
+        pub fn xrEndSession(session: XrSession) -> XrResult;
+        pub fn xrPollEvent(instance: XrInstance, eventData: *mut XrEventDataBuffer) -> XrResult;
+        pub fn xrStringToPath(
+            instance: XrInstance,
+            pathString: *const c_char,
+            path: *mut XrPath,
+        ) -> XrResult;
+        pub fn xrCreateActionSet(
+            instance: XrInstance,
+            createInfo: *const XrActionSetCreateInfo,
+            actionSet: *mut XrActionSet,
+        ) -> XrResult;
+        pub fn xrCreateAction(
+            actionSet: XrActionSet,
+            createInfo: *const XrActionCreateInfo,
+            action: *mut XrAction,
+        ) -> XrResult;
+        pub fn xrSuggestInteractionProfileBindings(
+            instance: XrInstance,
+            suggestedBindings: *const XrInteractionProfileSuggestedBinding,
+        ) -> XrResult;
+        pub fn xrAttachSessionActionSets(
+            session: XrSession,
+            attachInfo: *const XrSessionActionSetsAttachInfo,
+        ) -> XrResult;
+        pub fn xrSyncActions(session: XrSession, syncInfo: *const XrActionsSyncInfo) -> XrResult;
+        pub fn xrGetActionStateBoolean(
+            session: XrSession,
+            getInfo: *const XrActionStateGetInfo,
+            state: *mut XrActionStateBoolean,
+        ) -> XrResult;
+        pub fn xrGetActionStateFloat(
+            session: XrSession,
+            getInfo: *const XrActionStateGetInfo,
+            state: *mut XrActionStateFloat,
+        ) -> XrResult;
+        pub fn xrGetActionStateVector2f(
+            session: XrSession,
+            getInfo: *const XrActionStateGetInfo,
+            state: *mut XrActionStateVector2f,
+        ) -> XrResult;
+    }
+}
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    ptr,
+    time::Instant,
+};
+
+use bindings::*;
+
+use super::{
+    InputBackend, InputError, InputResult, InputState, BUTTON_A, BUTTON_B, BUTTON_ENTER,
+    BUTTON_L_THUMB, BUTTON_R_THUMB, BUTTON_X, BUTTON_Y, TOUCH_A, TOUCH_B, TOUCH_L_INDEX_POINTING,
+    TOUCH_L_INDEX_TRIGGER, TOUCH_L_THUMB, TOUCH_L_THUMB_REST, TOUCH_L_THUMB_UP,
+    TOUCH_R_INDEX_POINTING, TOUCH_R_INDEX_TRIGGER, TOUCH_R_THUMB, TOUCH_R_THUMB_REST,
+    TOUCH_R_THUMB_UP, TOUCH_X, TOUCH_Y,
+};
+
+// Headless sessions have no display to report a refresh rate for
+const POLL_RATE: f32 = 90.0;
+
+const BUTTON_ACTIONS: &[(&str, u32)] = &[
+    ("a", BUTTON_A),
+    ("b", BUTTON_B),
+    ("x", BUTTON_X),
+    ("y", BUTTON_Y),
+    ("l_thumb", BUTTON_L_THUMB),
+    ("r_thumb", BUTTON_R_THUMB),
+    ("menu", BUTTON_ENTER),
+];
+
+const TOUCH_ACTIONS: &[(&str, u32)] = &[
+    ("a_touch", TOUCH_A),
+    ("b_touch", TOUCH_B),
+    ("x_touch", TOUCH_X),
+    ("y_touch", TOUCH_Y),
+    ("l_thumb_touch", TOUCH_L_THUMB),
+    ("r_thumb_touch", TOUCH_R_THUMB),
+    ("l_thumb_rest", TOUCH_L_THUMB_REST),
+    ("r_thumb_rest", TOUCH_R_THUMB_REST),
+    ("l_index_touch", TOUCH_L_INDEX_TRIGGER),
+    ("r_index_touch", TOUCH_R_INDEX_TRIGGER),
+];
+
+// Index trigger, pointing, thumb surfaces and thumb up touches for each hand
+const HAND_POSES: [(u32, u32, u32, u32); 2] = [
+    (
+        TOUCH_L_INDEX_TRIGGER,
+        TOUCH_L_INDEX_POINTING,
+        TOUCH_X | TOUCH_Y | TOUCH_L_THUMB | TOUCH_L_THUMB_REST,
+        TOUCH_L_THUMB_UP,
+    ),
+    (
+        TOUCH_R_INDEX_TRIGGER,
+        TOUCH_R_INDEX_POINTING,
+        TOUCH_A | TOUCH_B | TOUCH_R_THUMB | TOUCH_R_THUMB_REST,
+        TOUCH_R_THUMB_UP,
+    ),
+];
+
+const FLOAT_ACTIONS: &[&str] = &["l_index", "r_index", "l_hand", "r_hand"];
+
+const VECTOR_ACTIONS: &[&str] = &["l_stick", "r_stick"];
+
+// Actions are laid out like the Touch controllers, other profiles bind what they have
+const PROFILES: &[(&str, &[(&str, &str)])] = &[
+    (
+        "/interaction_profiles/oculus/touch_controller",
+        &[
+            ("a", "/user/hand/right/input/a/click"),
+            ("b", "/user/hand/right/input/b/click"),
+            ("x", "/user/hand/left/input/x/click"),
+            ("y", "/user/hand/left/input/y/click"),
+            ("l_thumb", "/user/hand/left/input/thumbstick/click"),
+            ("r_thumb", "/user/hand/right/input/thumbstick/click"),
+            ("menu", "/user/hand/left/input/menu/click"),
+            ("a_touch", "/user/hand/right/input/a/touch"),
+            ("b_touch", "/user/hand/right/input/b/touch"),
+            ("x_touch", "/user/hand/left/input/x/touch"),
+            ("y_touch", "/user/hand/left/input/y/touch"),
+            ("l_thumb_touch", "/user/hand/left/input/thumbstick/touch"),
+            ("r_thumb_touch", "/user/hand/right/input/thumbstick/touch"),
+            ("l_thumb_rest", "/user/hand/left/input/thumbrest/touch"),
+            ("r_thumb_rest", "/user/hand/right/input/thumbrest/touch"),
+            ("l_index_touch", "/user/hand/left/input/trigger/touch"),
+            ("r_index_touch", "/user/hand/right/input/trigger/touch"),
+            ("l_index", "/user/hand/left/input/trigger/value"),
+            ("r_index", "/user/hand/right/input/trigger/value"),
+            ("l_hand", "/user/hand/left/input/squeeze/value"),
+            ("r_hand", "/user/hand/right/input/squeeze/value"),
+            ("l_stick", "/user/hand/left/input/thumbstick"),
+            ("r_stick", "/user/hand/right/input/thumbstick"),
+        ],
+    ),
+    (
+        "/interaction_profiles/valve/index_controller",
+        &[
+            ("a", "/user/hand/right/input/a/click"),
+            ("b", "/user/hand/right/input/b/click"),
+            ("x", "/user/hand/left/input/a/click"),
+            ("y", "/user/hand/left/input/b/click"),
+            ("l_thumb", "/user/hand/left/input/thumbstick/click"),
+            ("r_thumb", "/user/hand/right/input/thumbstick/click"),
+            ("a_touch", "/user/hand/right/input/a/touch"),
+            ("b_touch", "/user/hand/right/input/b/touch"),
+            ("x_touch", "/user/hand/left/input/a/touch"),
+            ("y_touch", "/user/hand/left/input/b/touch"),
+            ("l_thumb_touch", "/user/hand/left/input/thumbstick/touch"),
+            ("r_thumb_touch", "/user/hand/right/input/thumbstick/touch"),
+            ("l_index_touch", "/user/hand/left/input/trigger/touch"),
+            ("r_index_touch", "/user/hand/right/input/trigger/touch"),
+            ("l_index", "/user/hand/left/input/trigger/value"),
+            ("r_index", "/user/hand/right/input/trigger/value"),
+            ("l_hand", "/user/hand/left/input/squeeze/value"),
+            ("r_hand", "/user/hand/right/input/squeeze/value"),
+            ("l_stick", "/user/hand/left/input/thumbstick"),
+            ("r_stick", "/user/hand/right/input/thumbstick"),
+        ],
+    ),
+    (
+        "/interaction_profiles/khr/simple_controller",
+        &[
+            ("menu", "/user/hand/left/input/menu/click"),
+            ("l_index", "/user/hand/left/input/select/click"),
+            ("r_index", "/user/hand/right/input/select/click"),
+        ],
+    ),
+];
+
+#[derive(Debug)]
+pub struct OpenXr {
+    instance: XrInstance,
+    session: XrSession,
+    action_set: XrActionSet,
+    buttons: Vec<(XrAction, u32)>,
+    touches: Vec<(XrAction, u32)>,
+    floats: Vec<XrAction>,
+    vectors: Vec<XrAction>,
+    headset: String,
+    running: bool,
+    start: Instant,
+}
+
+impl InputBackend for OpenXr {
+    fn connect() -> InputResult<Self> {
+        unsafe {
+            if !headless_supported()? {
+                return Err(InputError {
+                    code: 0,
+                    reason: format!(
+                        "The OpenXR runtime doesn't support {XR_MND_HEADLESS_EXTENSION_NAME}"
+                    ),
+                });
+            }
+
+            let extension = CString::new(XR_MND_HEADLESS_EXTENSION_NAME).unwrap();
+            let extensions = [extension.as_ptr()];
+
+            let info = XrInstanceCreateInfo {
+                ty: XR_TYPE_INSTANCE_CREATE_INFO,
+                next: ptr::null(),
+                createFlags: 0,
+                applicationInfo: XrApplicationInfo {
+                    applicationName: name("Microwave"),
+                    applicationVersion: 0,
+                    engineName: name("Microwave"),
+                    engineVersion: 0,
+                    apiVersion: XR_CURRENT_API_VERSION,
+                },
+                enabledApiLayerCount: 0,
+                enabledApiLayerNames: ptr::null(),
+                enabledExtensionCount: extensions.len() as u32,
+                enabledExtensionNames: extensions.as_ptr(),
+            };
+
+            let mut instance = 0;
+            check(0, xrCreateInstance(&info, &mut instance))?;
+
+            let mut openxr = Self {
+                instance,
+                session: 0,
+                action_set: 0,
+                buttons: vec![],
+                touches: vec![],
+                floats: vec![],
+                vectors: vec![],
+                headset: String::new(),
+                running: false,
+                start: Instant::now(),
+            };
+
+            // Cleans up the instance if anything below fails
+            match openxr.setup() {
+                Ok(()) => Ok(openxr),
+                Err(error) => {
+                    openxr.shutdown();
+                    Err(error)
+                }
+            }
+        }
+    }
+
+    fn headset(&self) -> &str {
+        &self.headset
+    }
+
+    fn refresh_rate(&self) -> f32 {
+        POLL_RATE
+    }
+
+    fn poll(&mut self) -> InputResult<InputState> {
+        unsafe {
+            self.poll_events()?;
+
+            let mut state = InputState {
+                time: self.start.elapsed().as_secs_f64(),
+                ..Default::default()
+            };
+
+            if !self.running {
+                return Ok(state);
+            }
+
+            let active = XrActiveActionSet {
+                actionSet: self.action_set,
+                subactionPath: 0,
+            };
+
+            let info = XrActionsSyncInfo {
+                ty: XR_TYPE_ACTIONS_SYNC_INFO,
+                next: ptr::null(),
+                countActiveActionSets: 1,
+                activeActionSets: &active,
+            };
+
+            // Returns XR_SESSION_NOT_FOCUSED while another app has input, all actions read as
+            // inactive then
+            self.check(xrSyncActions(self.session, &info))?;
+
+            for &(action, button) in &self.buttons {
+                if self.boolean(action)? == Some(true) {
+                    state.buttons |= button;
+                }
+            }
+
+            let mut touch_active = 0;
+
+            for &(action, touch) in &self.touches {
+                if let Some(touched) = self.boolean(action)? {
+                    touch_active |= touch;
+
+                    if touched {
+                        state.touches |= touch;
+                    }
+                }
+            }
+
+            // LibOVR derives these from what the hand isn't touching
+            for (index, pointing, thumb, thumb_up) in HAND_POSES {
+                if touch_active & index != 0 && state.touches & index == 0 {
+                    state.touches |= pointing;
+                }
+
+                if touch_active & thumb != 0 && state.touches & thumb == 0 {
+                    state.touches |= thumb_up;
+                }
+            }
+
+            let mut floats = [0f32; 4];
+
+            for (&action, output) in self.floats.iter().zip(&mut floats) {
+                let mut value = XrActionStateFloat {
+                    ty: XR_TYPE_ACTION_STATE_FLOAT,
+                    next: ptr::null_mut(),
+                    currentState: 0.0,
+                    changedSinceLastSync: 0,
+                    lastChangeTime: 0,
+                    isActive: 0,
+                };
+
+                self.check(xrGetActionStateFloat(
+                    self.session,
+                    &get_info(action),
+                    &mut value,
+                ))?;
+
+                if value.isActive != 0 {
+                    *output = value.currentState;
+                }
+            }
+
+            state.index_trigger = [floats[0], floats[1]];
+            state.hand_trigger = [floats[2], floats[3]];
+
+            for (&action, output) in self.vectors.iter().zip(&mut state.thumbstick) {
+                let mut value = XrActionStateVector2f {
+                    ty: XR_TYPE_ACTION_STATE_VECTOR2F,
+                    next: ptr::null_mut(),
+                    currentState: [0.0; 2],
+                    changedSinceLastSync: 0,
+                    lastChangeTime: 0,
+                    isActive: 0,
+                };
+
+                self.check(xrGetActionStateVector2f(
+                    self.session,
+                    &get_info(action),
+                    &mut value,
+                ))?;
+
+                if value.isActive != 0 {
+                    *output = value.currentState;
+                }
+            }
+
+            Ok(state)
+        }
+    }
+
+    fn shutdown(&mut self) {
+        if self.instance != 0 {
+            // Destroying the instance destroys the session and actions with it
+            unsafe { xrDestroyInstance(self.instance) };
+
+            self.instance = 0;
+            self.session = 0;
+            self.running = false;
+        }
+    }
+}
+
+impl Drop for OpenXr {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+impl OpenXr {
+    unsafe fn setup(&mut self) -> InputResult {
+        let info = XrSystemGetInfo {
+            ty: XR_TYPE_SYSTEM_GET_INFO,
+            next: ptr::null(),
+            formFactor: XR_FORM_FACTOR_HEAD_MOUNTED_DISPLAY,
+        };
+
+        let mut system = 0;
+        self.check(xrGetSystem(self.instance, &info, &mut system))?;
+
+        let mut properties: XrSystemProperties = std::mem::zeroed();
+        properties.ty = XR_TYPE_SYSTEM_PROPERTIES;
+        self.check(xrGetSystemProperties(
+            self.instance,
+            system,
+            &mut properties,
+        ))?;
+
+        self.headset = CStr::from_ptr(properties.systemName.as_ptr())
+            .to_string_lossy()
+            .into_owned();
+
+        // XR_MND_headless allows a session without a graphics binding
+        let info = XrSessionCreateInfo {
+            ty: XR_TYPE_SESSION_CREATE_INFO,
+            next: ptr::null(),
+            createFlags: 0,
+            systemId: system,
+        };
+
+        let mut session = 0;
+        self.check(xrCreateSession(self.instance, &info, &mut session))?;
+        self.session = session;
+
+        let info = XrActionSetCreateInfo {
+            ty: XR_TYPE_ACTION_SET_CREATE_INFO,
+            next: ptr::null(),
+            actionSetName: name("microwave"),
+            localizedActionSetName: name("Microwave"),
+            priority: 0,
+        };
+
+        let mut action_set = 0;
+        self.check(xrCreateActionSet(self.instance, &info, &mut action_set))?;
+        self.action_set = action_set;
+
+        let mut actions = vec![];
+
+        for &(action_name, button) in BUTTON_ACTIONS {
+            let action = self.create_action(action_name, XR_ACTION_TYPE_BOOLEAN_INPUT)?;
+
+            self.buttons.push((action, button));
+            actions.push((action_name, action));
+        }
+
+        for &(action_name, touch) in TOUCH_ACTIONS {
+            let action = self.create_action(action_name, XR_ACTION_TYPE_BOOLEAN_INPUT)?;
+
+            self.touches.push((action, touch));
+            actions.push((action_name, action));
+        }
+
+        for &action_name in FLOAT_ACTIONS {
+            let action = self.create_action(action_name, XR_ACTION_TYPE_FLOAT_INPUT)?;
+
+            self.floats.push(action);
+            actions.push((action_name, action));
+        }
+
+        for &action_name in VECTOR_ACTIONS {
+            let action = self.create_action(action_name, XR_ACTION_TYPE_VECTOR2F_INPUT)?;
+
+            self.vectors.push(action);
+            actions.push((action_name, action));
+        }
+
+        for &(profile, bindings) in PROFILES {
+            let mut suggested = vec![];
+
+            for &(action_name, path) in bindings {
+                let Some(&(_, action)) = actions.iter().find(|(name, _)| *name == action_name)
+                else {
+                    continue;
+                };
+
+                suggested.push(XrActionSuggestedBinding {
+                    action,
+                    binding: self.path(path)?,
+                });
+            }
+
+            let info = XrInteractionProfileSuggestedBinding {
+                ty: XR_TYPE_INTERACTION_PROFILE_SUGGESTED_BINDING,
+                next: ptr::null(),
+                interactionProfile: self.path(profile)?,
+                countSuggestedBindings: suggested.len() as u32,
+                suggestedBindings: suggested.as_ptr(),
+            };
+
+            // Runtimes may reject profiles they don't know about, the others still apply
+            let _ = xrSuggestInteractionProfileBindings(self.instance, &info);
+        }
+
+        let info = XrSessionActionSetsAttachInfo {
+            ty: XR_TYPE_SESSION_ACTION_SETS_ATTACH_INFO,
+            next: ptr::null(),
+            countActionSets: 1,
+            actionSets: &self.action_set,
+        };
+
+        self.check(xrAttachSessionActionSets(self.session, &info))
+    }
+
+    // None while the action isn't bound to anything
+    unsafe fn boolean(&self, action: XrAction) -> InputResult<Option<bool>> {
+        let mut value = XrActionStateBoolean {
+            ty: XR_TYPE_ACTION_STATE_BOOLEAN,
+            next: ptr::null_mut(),
+            currentState: 0,
+            changedSinceLastSync: 0,
+            lastChangeTime: 0,
+            isActive: 0,
+        };
+
+        self.check(xrGetActionStateBoolean(
+            self.session,
+            &get_info(action),
+            &mut value,
+        ))?;
+
+        Ok((value.isActive != 0).then_some(value.currentState != 0))
+    }
+
+    unsafe fn create_action(&self, action_name: &str, action_type: i32) -> InputResult<XrAction> {
+        let info = XrActionCreateInfo {
+            ty: XR_TYPE_ACTION_CREATE_INFO,
+            next: ptr::null(),
+            actionName: name(action_name),
+            actionType: action_type,
+            countSubactionPaths: 0,
+            subactionPaths: ptr::null(),
+            localizedActionName: name(action_name),
+        };
+
+        let mut action = 0;
+        self.check(xrCreateAction(self.action_set, &info, &mut action))?;
+
+        Ok(action)
+    }
+
+    unsafe fn path(&self, path: &str) -> InputResult<XrPath> {
+        let string = CString::new(path).unwrap();
+
+        let mut path = 0;
+        self.check(xrStringToPath(self.instance, string.as_ptr(), &mut path))?;
+
+        Ok(path)
+    }
+
+    unsafe fn poll_events(&mut self) -> InputResult {
+        loop {
+            let mut event = XrEventDataBuffer {
+                ty: XR_TYPE_EVENT_DATA_BUFFER,
+                next: ptr::null(),
+                varying: [0; 4000],
+            };
+
+            let result = xrPollEvent(self.instance, &mut event);
+
+            if result == XR_EVENT_UNAVAILABLE {
+                return Ok(());
+            }
+
+            self.check(result)?;
+
+            match event.ty {
+                XR_TYPE_EVENT_DATA_SESSION_STATE_CHANGED => {
+                    let event = &*(&event as *const _ as *const XrEventDataSessionStateChanged);
+
+                    match event.state {
+                        XR_SESSION_STATE_READY => {
+                            let info = XrSessionBeginInfo {
+                                ty: XR_TYPE_SESSION_BEGIN_INFO,
+                                next: ptr::null(),
+                                primaryViewConfigurationType:
+                                    XR_VIEW_CONFIGURATION_TYPE_PRIMARY_STEREO,
+                            };
+
+                            self.check(xrBeginSession(self.session, &info))?;
+                            self.running = true;
+                        }
+                        XR_SESSION_STATE_STOPPING => {
+                            self.check(xrEndSession(self.session))?;
+                            self.running = false;
+                        }
+                        XR_SESSION_STATE_LOSS_PENDING | XR_SESSION_STATE_EXITING => {
+                            return Err(InputError {
+                                code: event.state,
+                                reason: "The OpenXR session ended".to_string(),
+                            });
+                        }
+                        _ => {}
+                    }
+                }
+                XR_TYPE_EVENT_DATA_INSTANCE_LOSS_PENDING => {
+                    return Err(InputError {
+                        code: 0,
+                        reason: "The OpenXR runtime is going away".to_string(),
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+
+    unsafe fn check(&self, result: XrResult) -> InputResult {
+        check(self.instance, result)
+    }
+}
+
+unsafe impl Send for OpenXr {}
+
+unsafe fn headless_supported() -> InputResult<bool> {
+    let mut count = 0;
+    check(
+        0,
+        xrEnumerateInstanceExtensionProperties(ptr::null(), 0, &mut count, ptr::null_mut()),
+    )?;
+
+    let mut properties: Vec<XrExtensionProperties> = (0..count)
+        .map(|_| XrExtensionProperties {
+            ty: XR_TYPE_EXTENSION_PROPERTIES,
+            next: ptr::null_mut(),
+            extensionName: [0; 128],
+            extensionVersion: 0,
+        })
+        .collect();
+
+    check(
+        0,
+        xrEnumerateInstanceExtensionProperties(
+            ptr::null(),
+            count,
+            &mut count,
+            properties.as_mut_ptr(),
+        ),
+    )?;
+
+    Ok(properties.iter().take(count as usize).any(|extension| {
+        CStr::from_ptr(extension.extensionName.as_ptr()).to_bytes()
+            == XR_MND_HEADLESS_EXTENSION_NAME.as_bytes()
+    }))
+}
+
+unsafe fn check(instance: XrInstance, result: XrResult) -> InputResult {
+    if result >= 0 {
+        return Ok(());
+    }
+
+    // Converting needs an instance, without one only the code is known
+    let reason = if instance == 0 {
+        "OpenXR call failed".to_string()
+    } else {
+        let mut buffer = [0; 64];
+        xrResultToString(instance, result, &mut buffer);
+
+        CStr::from_ptr(buffer.as_ptr())
+            .to_string_lossy()
+            .into_owned()
+    };
+
+    Err(InputError {
+        code: result,
+        reason,
+    })
+}
+
+fn get_info(action: XrAction) -> XrActionStateGetInfo {
+    XrActionStateGetInfo {
+        ty: XR_TYPE_ACTION_STATE_GET_INFO,
+        next: ptr::null(),
+        action,
+        subactionPath: 0,
+    }
+}
+
+fn name<const N: usize>(name: &str) -> [c_char; N] {
+    let mut output = [0; N];
+
+    for (output, &byte) in output.iter_mut().zip(name.as_bytes().iter().take(N - 1)) {
+        *output = byte as c_char;
+    }
+
+    output
+}
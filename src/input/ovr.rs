@@ -0,0 +1,160 @@
+mod bindings {
+    #![allow(non_upper_case_globals)]
+    #![allow(non_camel_case_types)]
+    #![allow(non_snake_case)]
+    #![allow(unused)]
+
+    include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
+}
+
+use bindings::{
+    ovrControllerType, ovrControllerType__ovrControllerType_Remote,
+    ovrControllerType__ovrControllerType_Touch, ovrControllerType__ovrControllerType_XBox,
+    ovrErrorInfo, ovrGraphicsLuid, ovrInitFlags__ovrInit_Invisible, ovrInitParams, ovrInputState,
+    ovrResult, ovrSession, ovr_Create, ovr_Destroy, ovr_GetConnectedControllerTypes,
+    ovr_GetHmdDesc, ovr_GetInputState, ovr_GetLastErrorInfo, ovr_Initialize, ovr_Shutdown,
+};
+
+use super::{InputBackend, InputError, InputResult, InputState};
+
+// Controllers whose states get merged into one, Touch covers either hand
+const CONTROLLER_TYPES: [ovrControllerType; 3] = [
+    ovrControllerType__ovrControllerType_Touch,
+    ovrControllerType__ovrControllerType_XBox,
+    ovrControllerType__ovrControllerType_Remote,
+];
+
+// I need to close the session when the window closes...
+pub static mut OVR_SESSION: ovrSession = std::ptr::null_mut();
+
+#[derive(Debug)]
+pub struct Ovr {
+    session: ovrSession,
+    headset: String,
+    refresh_rate: f32,
+}
+
+unsafe impl Send for Ovr {}
+unsafe impl Sync for Ovr {}
+
+impl InputBackend for Ovr {
+    fn connect() -> InputResult<Self> {
+        unsafe {
+            let params = ovrInitParams {
+                Flags: ovrInitFlags__ovrInit_Invisible as u32,
+                RequestedMinorVersion: 0,
+                LogCallback: None,
+                UserData: 0,
+                ConnectionTimeoutMS: 0,
+                pad0: std::mem::zeroed(),
+            };
+
+            ovr_Initialize(&params).check()?;
+
+            let mut session: ovrSession = std::mem::zeroed();
+            let mut luid: ovrGraphicsLuid = std::mem::zeroed();
+            ovr_Create(&mut session, &mut luid).check()?;
+
+            OVR_SESSION = session;
+
+            let desc = ovr_GetHmdDesc(session);
+
+            Ok(Self {
+                session,
+                headset: char_array_to_string(&desc.ProductName),
+                refresh_rate: desc.DisplayRefreshRate,
+            })
+        }
+    }
+
+    fn headset(&self) -> &str {
+        &self.headset
+    }
+
+    fn refresh_rate(&self) -> f32 {
+        self.refresh_rate
+    }
+
+    fn poll(&mut self) -> InputResult<InputState> {
+        unsafe {
+            let connected = ovr_GetConnectedControllerTypes(self.session);
+
+            let mut controllers: Vec<_> = CONTROLLER_TYPES
+                .into_iter()
+                .filter(|&controller| connected & controller as u32 != 0)
+                .collect();
+
+            // Still poll Touch so the state keeps its timestamp while nothing is connected
+            if controllers.is_empty() {
+                controllers.push(ovrControllerType__ovrControllerType_Touch);
+            }
+
+            let mut merged = InputState::default();
+
+            for controller in controllers {
+                let mut state: ovrInputState = std::mem::zeroed();
+                ovr_GetInputState(self.session, controller, &mut state).check()?;
+
+                merged.time = merged.time.max(state.TimeInSeconds);
+                merged.buttons |= state.Buttons;
+                merged.touches |= state.Touches;
+
+                // The controller applies its own thresholds, the SDK deadzone would only skew them
+                for hand in 0..2 {
+                    merged.index_trigger[hand] =
+                        merged.index_trigger[hand].max(state.IndexTriggerNoDeadzone[hand]);
+                    merged.hand_trigger[hand] =
+                        merged.hand_trigger[hand].max(state.HandTriggerNoDeadzone[hand]);
+
+                    let stick = state.Thumbstick[hand];
+                    let [x, y] = merged.thumbstick[hand];
+
+                    if stick.x.hypot(stick.y) > x.hypot(y) {
+                        merged.thumbstick[hand] = [stick.x, stick.y];
+                    }
+                }
+            }
+
+            Ok(merged)
+        }
+    }
+
+    fn shutdown(&mut self) {
+        unsafe {
+            Ovr::destroy(self.session);
+            OVR_SESSION = std::ptr::null_mut();
+        }
+    }
+}
+
+impl Ovr {
+    pub unsafe fn destroy(session: ovrSession) {
+        ovr_Destroy(session);
+        ovr_Shutdown();
+    }
+}
+
+trait OvrResultCheck {
+    unsafe fn check(self) -> InputResult;
+}
+
+impl OvrResultCheck for ovrResult {
+    unsafe fn check(self) -> InputResult {
+        if self < 0 {
+            let mut info: ovrErrorInfo = std::mem::zeroed();
+            ovr_GetLastErrorInfo(&mut info);
+
+            Err(InputError {
+                code: info.Result,
+                reason: char_array_to_string(&info.ErrorString),
+            })
+        } else {
+            Ok(())
+        }
+    }
+}
+
+fn char_array_to_string(input: &[i8]) -> String {
+    String::from_utf8(input.iter().map(|&c| c as u8).filter(|&c| c != 0).collect())
+        .unwrap_or("Unknown".to_string())
+}
@@ -0,0 +1,228 @@
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::Path,
+};
+
+use super::InputState;
+
+// File layout, all little endian:
+// header: b"MWRC", version u8, refresh rate f32, headset name length u8, headset name
+// frames: time f64, buttons u32, touches u32, index l/r, hand l/r, thumbsticks lx ly rx ry as f32
+// Frames are only written when the input changes, times are relative to the first frame
+const MAGIC: &[u8; 4] = b"MWRC";
+const VERSION: u8 = 1;
+
+pub struct Recorder {
+    writer: BufWriter<File>,
+    start: Option<f64>,
+    last: Option<InputState>,
+}
+
+impl Recorder {
+    pub fn create(path: impl AsRef<Path>, headset: &str, refresh_rate: f32) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        let name = &headset.as_bytes()[..headset.len().min(u8::MAX as usize)];
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        writer.write_all(&refresh_rate.to_le_bytes())?;
+        writer.write_all(&[name.len() as u8])?;
+        writer.write_all(name)?;
+        writer.flush()?;
+
+        Ok(Self {
+            writer,
+            start: None,
+            last: None,
+        })
+    }
+
+    pub fn record(&mut self, state: &InputState) -> io::Result<()> {
+        let input = InputState {
+            time: 0.0,
+            ..*state
+        };
+
+        if self.last == Some(input) {
+            return Ok(());
+        }
+
+        let start = *self.start.get_or_insert(state.time);
+
+        self.writer.write_all(&(state.time - start).to_le_bytes())?;
+        self.writer.write_all(&state.buttons.to_le_bytes())?;
+        self.writer.write_all(&state.touches.to_le_bytes())?;
+
+        let axes = state
+            .index_trigger
+            .iter()
+            .chain(&state.hand_trigger)
+            .chain(state.thumbstick.iter().flatten());
+
+        for axis in axes {
+            self.writer.write_all(&axis.to_le_bytes())?;
+        }
+
+        // Flushed per frame so a crash still leaves a usable recording
+        self.writer.flush()?;
+        self.last = Some(input);
+
+        Ok(())
+    }
+}
+
+pub struct Recording {
+    pub headset: String,
+    pub refresh_rate: f32,
+    pub frames: Vec<InputState>,
+}
+
+impl Recording {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+
+        let invalid = |reason| io::Error::new(io::ErrorKind::InvalidData, reason);
+
+        if read::<4>(&mut reader)? != *MAGIC {
+            return Err(invalid("Not a Microwave recording"));
+        }
+
+        if read::<1>(&mut reader)?[0] != VERSION {
+            return Err(invalid("Unsupported recording version"));
+        }
+
+        let refresh_rate = f32::from_le_bytes(read(&mut reader)?);
+
+        // Polling is timed by it, so a bad file must not get that far
+        if !refresh_rate.is_finite() || refresh_rate <= 0.0 {
+            return Err(invalid("Invalid refresh rate"));
+        }
+
+        let mut name = vec![0; read::<1>(&mut reader)?[0] as usize];
+        reader.read_exact(&mut name)?;
+
+        let mut frames = vec![];
+
+        loop {
+            let time = match read(&mut reader) {
+                Ok(bytes) => f64::from_le_bytes(bytes),
+                Err(error) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+                Err(error) => return Err(error),
+            };
+
+            let buttons = u32::from_le_bytes(read(&mut reader)?);
+            let touches = u32::from_le_bytes(read(&mut reader)?);
+
+            let mut axes = [0f32; 8];
+
+            for axis in &mut axes {
+                *axis = f32::from_le_bytes(read(&mut reader)?);
+            }
+
+            frames.push(InputState {
+                time,
+                buttons,
+                touches,
+                index_trigger: [axes[0], axes[1]],
+                hand_trigger: [axes[2], axes[3]],
+                thumbstick: [[axes[4], axes[5]], [axes[6], axes[7]]],
+            });
+        }
+
+        Ok(Self {
+            headset: String::from_utf8_lossy(&name).into_owned(),
+            refresh_rate,
+            frames,
+        })
+    }
+}
+
+fn read<const N: usize>(reader: &mut impl Read) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use super::*;
+
+    fn path(name: &str) -> std::path::PathBuf {
+        env::temp_dir().join(format!("microwave-{name}-{}.mwrc", process::id()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = path("round-trip");
+
+        let frames = [
+            InputState {
+                time: 5.0,
+                ..InputState::default()
+            },
+            InputState {
+                time: 5.25,
+                buttons: 0x404,
+                touches: 0x8,
+                index_trigger: [0.9, 0.1],
+                hand_trigger: [0.5, 1.0],
+                thumbstick: [[-1.0, 0.25], [0.0, -0.5]],
+            },
+            // Unchanged input isn't written again
+            InputState {
+                time: 5.5,
+                buttons: 0x404,
+                touches: 0x8,
+                index_trigger: [0.9, 0.1],
+                hand_trigger: [0.5, 1.0],
+                thumbstick: [[-1.0, 0.25], [0.0, -0.5]],
+            },
+        ];
+
+        let mut recorder = Recorder::create(&path, "Quest 3", 90.0).unwrap();
+
+        for frame in &frames {
+            recorder.record(frame).unwrap();
+        }
+
+        drop(recorder);
+
+        let recording = Recording::open(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(recording.headset, "Quest 3");
+        assert_eq!(recording.refresh_rate, 90.0);
+        assert_eq!(
+            recording.frames,
+            [
+                InputState {
+                    time: 0.0,
+                    ..frames[0]
+                },
+                InputState {
+                    time: 0.25,
+                    ..frames[1]
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_refresh_rates() {
+        for (name, refresh_rate) in [("zero", 0.0), ("negative", -72.0), ("nan", f32::NAN)] {
+            let path = path(name);
+
+            Recorder::create(&path, "Quest 3", refresh_rate).unwrap();
+
+            let error = Recording::open(&path).err().unwrap();
+            let _ = fs::remove_file(&path);
+
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,38 @@
+use std::env;
+
+use super::{record::Recording, sim::Sim, InputBackend, InputResult, InputState};
+
+// Plays back a file written by the recorder through the same binding logic as a live headset
+#[derive(Debug)]
+pub struct Replay {
+    sim: Sim,
+    headset: String,
+}
+
+impl InputBackend for Replay {
+    fn connect() -> InputResult<Self> {
+        let path = env::var("MICROWAVE_REPLAY").unwrap_or_default();
+        let recording = Recording::open(path)?;
+
+        Ok(Self {
+            sim: Sim::new(recording.frames).with_refresh_rate(recording.refresh_rate),
+            headset: format!("{} (Replay)", recording.headset),
+        })
+    }
+
+    fn headset(&self) -> &str {
+        &self.headset
+    }
+
+    fn refresh_rate(&self) -> f32 {
+        self.sim.refresh_rate()
+    }
+
+    fn poll(&mut self) -> InputResult<InputState> {
+        self.sim.poll()
+    }
+
+    fn shutdown(&mut self) {
+        self.sim.shutdown();
+    }
+}
@@ -0,0 +1,132 @@
+use std::{env, fs};
+
+use super::{InputBackend, InputError, InputResult, InputState};
+
+// Scripted controller for running without a headset, each frame is held from its
+// timestamp until the next one and the clock advances one refresh per poll
+#[derive(Debug, Clone)]
+pub struct Sim {
+    frames: Vec<InputState>,
+    next: usize,
+    clock: f64,
+    refresh_rate: f32,
+    state: InputState,
+}
+
+impl Sim {
+    pub fn new(mut frames: Vec<InputState>) -> Self {
+        frames.sort_by(|a, b| a.time.total_cmp(&b.time));
+
+        Self {
+            frames,
+            next: 0,
+            clock: 0.0,
+            refresh_rate: 72.0,
+            state: InputState::default(),
+        }
+    }
+
+    pub fn with_refresh_rate(mut self, refresh_rate: f32) -> Self {
+        self.refresh_rate = refresh_rate;
+        self
+    }
+
+    // One frame per line: time buttons touches l_index r_index l_hand r_hand lx ly rx ry
+    // Buttons and touches are hex masks, missing trailing values are zero
+    pub fn parse(script: &str) -> InputResult<Self> {
+        let mut frames = vec![];
+
+        for (i, line) in script.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or_default().trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            let error = |reason: &str| InputError {
+                code: 0,
+                reason: format!("Sim script line {}: {reason}", i + 1),
+            };
+
+            let mut fields = line.split_whitespace();
+
+            let time = fields
+                .next()
+                .and_then(|time| time.parse().ok())
+                .ok_or_else(|| error("invalid time"))?;
+
+            let mut masks = [0u32; 2];
+
+            for (mask, field) in masks.iter_mut().zip(fields.by_ref()) {
+                *mask = u32::from_str_radix(field.trim_start_matches("0x"), 16)
+                    .map_err(|_| error("invalid mask"))?;
+            }
+
+            let mut axes = [0f32; 8];
+
+            for (axis, field) in axes.iter_mut().zip(fields.by_ref()) {
+                *axis = field.parse().map_err(|_| error("invalid axis"))?;
+            }
+
+            if fields.next().is_some() {
+                return Err(error("too many values"));
+            }
+
+            frames.push(InputState {
+                time,
+                buttons: masks[0],
+                touches: masks[1],
+                index_trigger: [axes[0], axes[1]],
+                hand_trigger: [axes[2], axes[3]],
+                thumbstick: [[axes[4], axes[5]], [axes[6], axes[7]]],
+            });
+        }
+
+        Ok(Self::new(frames))
+    }
+}
+
+impl InputBackend for Sim {
+    // Reads the script from the file in MICROWAVE_SIM, idles with nothing pressed otherwise
+    fn connect() -> InputResult<Self> {
+        match env::var("MICROWAVE_SIM") {
+            Ok(path) if !path.is_empty() => {
+                let script = fs::read_to_string(&path).map_err(|error| InputError {
+                    code: error.raw_os_error().unwrap_or_default(),
+                    reason: format!("Unable to read {path}: {error}"),
+                })?;
+
+                Self::parse(&script)
+            }
+            _ => Ok(Self::new(vec![])),
+        }
+    }
+
+    fn headset(&self) -> &str {
+        "Simulated Headset"
+    }
+
+    fn refresh_rate(&self) -> f32 {
+        self.refresh_rate
+    }
+
+    fn poll(&mut self) -> InputResult<InputState> {
+        while let Some(frame) = self.frames.get(self.next) {
+            if frame.time > self.clock {
+                break;
+            }
+
+            self.state = *frame;
+            self.next += 1;
+        }
+
+        self.state.time = self.clock;
+        self.clock += 1.0 / self.refresh_rate as f64;
+
+        Ok(self.state)
+    }
+
+    fn shutdown(&mut self) {
+        self.next = self.frames.len();
+    }
+}
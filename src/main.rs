@@ -3,18 +3,27 @@
     windows_subsystem = "windows"
 )]
 
-mod microphone;
-mod ovr;
+mod apps;
+mod audio;
+mod controller;
+mod input;
 mod poller;
 mod screen;
+mod settings;
+
+use std::{env, iter, time::Duration};
 
 use iced::{
+    time,
     window::{icon, Settings},
     Element, Subscription, Task, Theme,
 };
+#[cfg(target_os = "windows")]
 use windows::Win32::System::Com::{CoInitializeEx, CoUninitialize, COINIT_APARTMENTTHREADED};
 
-use ovr::{Ovr, OvrError, OVR_SESSION};
+#[cfg(all(target_os = "windows", feature = "ovr"))]
+use input::ovr::{Ovr, OVR_SESSION};
+use input::{replay::Replay, sim::Sim, DefaultBackend, InputError};
 use poller::Event;
 use screen::{
     error::{self, Error},
@@ -22,13 +31,16 @@ use screen::{
     ready::{self, Ready},
 };
 
+// How often running apps are checked for profile switching
+const APP_POLL: Duration = Duration::from_secs(2);
+
 struct Microwave {
     state: State,
 }
 
 enum State {
     Loading,
-    Ready(Ready),
+    Ready(Box<Ready>),
     Error(Error),
 }
 
@@ -41,6 +53,7 @@ enum Message {
 }
 
 fn main() -> iced::Result {
+    #[cfg(target_os = "windows")]
     unsafe {
         CoInitializeEx(None, COINIT_APARTMENTTHREADED).unwrap();
     }
@@ -56,17 +69,38 @@ fn main() -> iced::Result {
         .run_with(Microwave::new)?;
 
     // TODO: Probably replaceable with an on application close callback
+    #[cfg(all(target_os = "windows", feature = "ovr"))]
     unsafe {
         if !OVR_SESSION.is_null() {
-            Ovr::shutdown(OVR_SESSION);
+            Ovr::destroy(OVR_SESSION);
         }
+    }
 
+    #[cfg(target_os = "windows")]
+    unsafe {
         CoUninitialize();
     }
 
     Ok(())
 }
 
+fn controller_events() -> Subscription<Event> {
+    if env::var_os("MICROWAVE_REPLAY").is_some() {
+        return Subscription::run(poller::poll::<Replay>);
+    }
+
+    if env::var_os("MICROWAVE_SIM").is_some() {
+        return Subscription::run(poller::poll::<Sim>);
+    }
+
+    #[cfg(feature = "openxr")]
+    if env::var_os("MICROWAVE_OPENXR").is_some() {
+        return Subscription::run(poller::poll::<input::openxr::OpenXr>);
+    }
+
+    Subscription::run(poller::poll::<DefaultBackend>)
+}
+
 impl Microwave {
     fn new() -> (Self, Task<Message>) {
         (
@@ -83,18 +117,40 @@ impl Microwave {
 
     fn subscription(&self) -> Subscription<Message> {
         if let State::Error(_) = &self.state {
-            Subscription::none()
-        } else {
-            Subscription::run(poller::poll).map(|event| match event {
-                Event::Ready(headset, sender) => {
-                    Message::Loading(loading::Message::Ready((headset, sender)))
-                }
-                Event::Controller(event) => Message::Ready(ready::Message::Controller(event)),
-                Event::Error(OvrError { code, reason }) => {
-                    Message::Errored(format!("OVR Error\nCode {code}\nReason {reason}"))
-                }
-            })
+            return Subscription::none();
         }
+
+        let controller = controller_events().map(|event| match event {
+            Event::Ready(headset, sender) => {
+                Message::Loading(loading::Message::Ready((headset, sender)))
+            }
+            Event::Controller(event) => Message::Ready(ready::Message::Controller(event)),
+            Event::Error(InputError { code, reason }) => {
+                Message::Errored(format!("Input Error\nCode {code}\nReason {reason}"))
+            }
+        });
+
+        let State::Ready(ready) = &self.state else {
+            return controller;
+        };
+
+        // Restarts with the new mics whenever they change
+        let mutes = iter::once(&ready.mic).chain(&ready.group).map(|mic| {
+            Subscription::run_with_id(mic.id.clone(), audio::watch_mute(mic.id.clone()))
+                .with(mic.id.clone())
+                .map(|(id, event)| Message::Ready(ready::Message::MuteChanged(id, event)))
+        });
+
+        Subscription::batch(
+            [
+                controller,
+                time::every(APP_POLL).map(|_| Message::Ready(ready::Message::DetectApps)),
+                Subscription::run(audio::watch)
+                    .map(|event| Message::Ready(ready::Message::Devices(event))),
+            ]
+            .into_iter()
+            .chain(mutes),
+        )
     }
 
     fn update(&mut self, message: Message) {
@@ -126,7 +182,7 @@ impl Microwave {
         };
     }
 
-    fn view(&self) -> Element<Message> {
+    fn view(&self) -> Element<'_, Message> {
         match &self.state {
             State::Loading => loading::view().map(Message::Loading),
             State::Ready(ready) => ready.view().map(Message::Ready),
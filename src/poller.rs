@@ -1,65 +1,109 @@
-use std::time::Duration;
+use std::{env, time::Duration};
 
 use iced::{
     futures::{channel::mpsc, SinkExt, Stream, StreamExt},
     stream,
 };
 
-use crate::ovr::{ControllerEvent, Ovr, OvrError, OVR_SESSION};
+use crate::{
+    controller::{Binding, Controller, ControllerEvent, TriggerThreshold},
+    input::{record::Recorder, InputBackend, InputError},
+};
 
 #[derive(Debug, Clone)]
 pub enum Event {
     Ready(String, mpsc::Sender<Message>),
     Controller(ControllerEvent),
-    Error(OvrError),
+    Error(InputError),
 }
 
 pub enum Message {
-    SettingBind,
+    SettingBind(usize),
+    // Stops capturing and restores the binding being set
+    CancelBind,
+    Bindings(Vec<Binding>),
+    // Stick deadzone and direction sector
+    Thumbsticks(f32, f32),
+    TriggerThresholds([TriggerThreshold; 4]),
 }
 
-pub fn poll() -> impl Stream<Item = Event> {
+pub fn poll<B: InputBackend + 'static>() -> impl Stream<Item = Event> {
     stream::channel(64, move |mut output| async move {
         let (sender, mut receiver) = mpsc::channel(64);
 
-        let mut ovr = match unsafe { Ovr::new() } {
-            Ok(ovr) => {
-                unsafe { OVR_SESSION = ovr.session };
-                ovr
-            }
+        let mut backend = match B::connect() {
+            Ok(backend) => backend,
             Err(error) => {
                 let _ = output.send(Event::Error(error)).await;
                 return;
             }
         };
 
-        let interval = Duration::from_secs_f32(1000.0 / ovr.refresh_rate / 1000.0);
+        let mut recorder = match env::var_os("MICROWAVE_RECORD") {
+            Some(path) => match Recorder::create(path, backend.headset(), backend.refresh_rate()) {
+                Ok(recorder) => Some(recorder),
+                Err(error) => {
+                    let _ = output.send(Event::Error(error.into())).await;
+
+                    backend.shutdown();
+
+                    return;
+                }
+            },
+            None => None,
+        };
+
+        let mut controller = Controller::default();
+
+        let interval = Duration::from_secs_f32(1000.0 / backend.refresh_rate() / 1000.0);
 
-        let _ = output.send(Event::Ready(ovr.headset.clone(), sender)).await;
+        let _ = output
+            .send(Event::Ready(backend.headset().to_string(), sender))
+            .await;
 
         loop {
             tokio::select! {
-                message = receiver.next() => {
-                    if let Some(Message::SettingBind) = message {
-                        ovr.start_setting_binding();
+                message = receiver.next() => match message {
+                    Some(Message::SettingBind(index)) => controller.start_setting_binding(index),
+                    Some(Message::CancelBind) => {
+                        if let Some(event) = controller.cancel_setting_binding() {
+                            let _ = output.send(Event::Controller(event)).await;
+                        }
                     }
-                }
+                    Some(Message::Bindings(bindings)) => controller.set_bindings(bindings),
+                    Some(Message::Thumbsticks(deadzone, sector)) => {
+                        controller.stick_deadzone = deadzone;
+                        controller.stick_sector = sector;
+                    }
+                    Some(Message::TriggerThresholds(thresholds)) => {
+                        controller.trigger_thresholds = thresholds;
+                    }
+                    None => {}
+                },
                 () = tokio::time::sleep(interval) => {
-                    match unsafe { ovr.poll_input() } {
-                        Ok(Some(event)) => {
-                            let _ = output.send(Event::Controller(event)).await;
+                    match backend.poll() {
+                        Ok(state) => {
+                            if let Some(recorder) = &mut recorder {
+                                if let Err(error) = recorder.record(&state) {
+                                    let _ = output.send(Event::Error(error.into())).await;
+
+                                    backend.shutdown();
+
+                                    return;
+                                }
+                            }
+
+                            for event in controller.update(&state) {
+                                let _ = output.send(Event::Controller(event)).await;
+                            }
                         },
                         Err(error) => {
                             let _ = output.send(Event::Error(error)).await;
 
-                            unsafe {
-                                Ovr::shutdown(OVR_SESSION);
-                                OVR_SESSION = std::ptr::null_mut();
-                            }
+                            backend.shutdown();
 
                             return;
                         },
-                        _ => {}
                     }
                 }
             };
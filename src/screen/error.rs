@@ -3,12 +3,20 @@ use iced::{
     Element, Length,
 };
 
-use crate::State;
+use crate::{audio::AudioError, State};
 
 pub struct Error {
     pub error: String,
 }
 
+impl From<AudioError> for Error {
+    fn from(AudioError { code, reason }: AudioError) -> Self {
+        Self {
+            error: format!("Audio Error\nCode {code}\nReason {reason}"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Message {
     Retry,
@@ -5,11 +5,14 @@ use iced::{
 };
 use rodio::OutputStream;
 
-use super::{
-    error::Error,
-    ready::{MicMode, Ready},
+use super::{error::Error, ready::Ready};
+use crate::{
+    apps,
+    audio::{self, Role},
+    poller,
+    settings::Settings,
+    State,
 };
-use crate::{microphone, poller, State};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -18,28 +21,78 @@ pub enum Message {
 
 pub fn update(message: Message) -> State {
     match message {
-        Message::Ready((headset, poller)) => match unsafe { microphone::active() } {
-            Ok(mics) if !mics.is_empty() => State::Ready(Ready {
+        Message::Ready((headset, mut poller)) => {
+            let settings = Settings::load();
+
+            let backend = match audio::connect() {
+                Ok(backend) => backend,
+                Err(error) => return State::Error(error.into()),
+            };
+
+            let (mics, default, communications) = match (
+                backend.microphones(),
+                backend.default_microphone(Role::Console),
+                backend.default_microphone(Role::Communications),
+            ) {
+                (Ok(mics), Ok(default), Ok(communications)) => (mics, default, communications),
+                (Err(error), _, _) | (_, Err(error), _) | (_, _, Err(error)) => {
+                    return State::Error(error.into())
+                }
+            };
+
+            let preferred = if settings.follow_default {
+                communications.as_deref()
+            } else {
+                settings.microphone.as_deref()
+            };
+
+            let Some(mic) = audio::pick_microphone(&mics, preferred.as_slice(), default.as_deref())
+            else {
+                return State::Error(Error {
+                    error: "No microphones found".to_string(),
+                });
+            };
+
+            let profile = &settings.profiles[settings.profile];
+
+            let _ = poller.try_send(poller::Message::Bindings(profile.controller_bindings()));
+            let _ = poller.try_send(poller::Message::TriggerThresholds(
+                settings.trigger_thresholds,
+            ));
+            let _ = poller.try_send(poller::Message::Thumbsticks(
+                settings.stick_deadzone,
+                settings.stick_sector,
+            ));
+
+            let mut ready = Ready {
                 poller,
                 headset,
-                mic: mics
-                    .iter()
-                    .find(|mic| mic.name.contains("Headset Microphone"))
-                    .unwrap_or_else(|| &mics[0])
-                    .clone(),
-                mics: mics.into_iter().map(|mic| mic.name).collect(),
-                mode: MicMode::MuteAndUnmute,
-                binding: "L Thumb + R Thumb".to_string(),
-                is_setting_binding: false,
+                backend,
+                mic,
+                group: vec![],
+                mics,
+                gain: None,
+                volume: None,
+                apps: apps::connect(),
+                profile: settings.profile,
+                detected: None,
+                settings,
+                setting_binding: None,
+                hybrid_muted: false,
+                external_mute: false,
+                mute_errors: vec![],
                 audio: OutputStream::try_default().ok(),
-            }),
-            Ok(_) => State::Error(Error {
-                error: "No microphones found".to_string(),
-            }),
-            Err(error) => State::Error(Error {
-                error: error.to_string(),
-            }),
-        },
+            };
+
+            ready.refresh_group();
+            ready.refresh_levels();
+
+            // Push to talk and push to mute start out as if the binding is released, failures are
+            // shown per mic
+            ready.release_mute();
+
+            State::Ready(Box::new(ready))
+        }
     }
 }
 
@@ -1,19 +1,26 @@
-use std::io::Cursor;
+use std::{fmt, io::Cursor, iter, time::Duration};
 
 use iced::{
-    alignment::Vertical,
+    alignment::{Horizontal, Vertical},
     color,
     futures::channel::mpsc,
-    widget::{button, column, container, pick_list, radio, row, svg, text},
+    widget::{
+        button, checkbox, column, container, pick_list, radio, row, scrollable, slider, svg, text,
+        text_input,
+    },
     Element, Length,
 };
-use rodio::{Decoder, OutputStream, OutputStreamHandle, Source};
+use rodio::{source::SineWave, Decoder, OutputStream, OutputStreamHandle, Source};
+use serde::{Deserialize, Serialize};
 
 use super::error::Error;
 use crate::{
-    microphone::{self, Microphone},
-    ovr::{self, ControllerEvent},
-    poller, State,
+    apps::{self, AppSource},
+    audio::{self, AudioBackend, AudioError, DeviceEvent, Gain, Microphone, MuteEvent, Role},
+    controller::{self, Binding, Conflict, ConflictKind, ControllerEvent, TouchCondition},
+    poller,
+    settings::{ActionBinding, Profile, Settings},
+    State,
 };
 
 const MUTED_SVG: &[u8] = include_bytes!("../../res/muted.svg");
@@ -22,79 +29,240 @@ const UNMUTED_SVG: &[u8] = include_bytes!("../../res/unmuted.svg");
 const MUTED_AUDIO: Cursor<&[u8]> = Cursor::new(include_bytes!("../../res/mute.wav"));
 const UNMUTED_AUDIO: Cursor<&[u8]> = Cursor::new(include_bytes!("../../res/unmute.wav"));
 
+pub const GAIN_STEP: f32 = 3.0;
+
+// Gain feedback goes up an octave from the lowest gain to the highest
+const GAIN_TONE: f32 = 440.0;
+const GAIN_TONE_LENGTH: Duration = Duration::from_millis(80);
+
+const DEFAULT_MIC: &str = "System default (communications)";
+
+// Same order as `Settings::trigger_thresholds`
+const TRIGGERS: [&str; 4] = [
+    "L Index Trigger",
+    "R Index Trigger",
+    "L Hand Trigger",
+    "R Hand Trigger",
+];
+
 pub struct Ready {
     pub poller: mpsc::Sender<poller::Message>,
     pub headset: String,
+    pub backend: Box<dyn AudioBackend>,
     pub mic: Microphone,
-    pub mics: Vec<String>,
-    pub mode: MicMode,
-    pub binding: String,
-    pub is_setting_binding: bool,
+    // Other plugged in mics muted and unmuted along with `mic`
+    pub group: Vec<Microphone>,
+    pub mics: Vec<Microphone>,
+    // Gain of `mic`, None when it can't be read
+    pub gain: Option<Gain>,
+    // Linear volume of `mic` as the system sound settings show it
+    pub volume: Option<f32>,
+    pub apps: Box<dyn AppSource>,
+    // Profile in use, either picked by hand or switched to for a running app
+    pub profile: usize,
+    // Profile whose app was last found running
+    pub detected: Option<usize>,
+    pub settings: Settings,
+    // Controller binding index and the binding captured so far
+    pub setting_binding: Option<(usize, Binding)>,
+    // Mute state from before a hybrid binding was pressed
+    pub hybrid_muted: bool,
+    // The mic was last muted or unmuted by something other than Microwave
+    pub external_mute: bool,
+    // Mics that failed the last mute change, with the reason
+    pub mute_errors: Vec<String>,
     pub audio: Option<(OutputStream, OutputStreamHandle)>,
 }
 
+// Mute state across `Ready::mic` and its group
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupMute {
+    Muted,
+    Live,
+    Mixed,
+}
+
+// Profiles are picked by index, names can repeat
+#[derive(Debug, Clone, PartialEq)]
+struct ProfileOption(usize, String);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MicMode {
     MuteAndUnmute,
     PushToTalk,
+    PushToMute,
+    // Tap to toggle, hold past the threshold for push to talk
+    Hybrid,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Action {
+    ToggleMute,
+    PushToTalk,
+    PushToMute,
+    Hybrid,
+    #[serde(alias = "VolumeUp")]
+    GainUp,
+    #[serde(alias = "VolumeDown")]
+    GainDown,
+    GainPreset,
+    SwitchMicrophone,
+    PlaySound,
+}
+
+impl MicMode {
+    pub fn action(self) -> Action {
+        match self {
+            MicMode::MuteAndUnmute => Action::ToggleMute,
+            MicMode::PushToTalk => Action::PushToTalk,
+            MicMode::PushToMute => Action::PushToMute,
+            MicMode::Hybrid => Action::Hybrid,
+        }
+    }
+}
+
+impl Action {
+    const ALL: [Action; 9] = [
+        Action::ToggleMute,
+        Action::PushToTalk,
+        Action::PushToMute,
+        Action::Hybrid,
+        Action::GainUp,
+        Action::GainDown,
+        Action::GainPreset,
+        Action::SwitchMicrophone,
+        Action::PlaySound,
+    ];
+}
+
+impl fmt::Display for ProfileOption {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.1)
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Action::ToggleMute => "Mute / Unmute",
+            Action::PushToTalk => "Push To Talk",
+            Action::PushToMute => "Push To Mute",
+            Action::Hybrid => "Tap To Toggle / Hold To Talk",
+            Action::GainUp => "Gain Up",
+            Action::GainDown => "Gain Down",
+            Action::GainPreset => "Set Gain",
+            Action::SwitchMicrophone => "Switch Microphone",
+            Action::PlaySound => "Play Mute State Sound",
+        })
+    }
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
-    Controller(ovr::ControllerEvent),
+    Controller(ControllerEvent),
     MuteToggle,
     MicMode(MicMode),
     MicSelected(String),
-    SettingControllerBind,
+    MicGrouped(String, bool),
+    Gain(f32),
+    Volume(f32),
+    Devices(DeviceEvent),
+    MuteChanged(String, MuteEvent),
+    DetectApps,
+    ProfileSelected(usize),
+    AddProfile,
+    RemoveProfile,
+    ProfileName(String),
+    ProfileApps(String),
+    SettingControllerBind(usize),
+    CancelControllerBind,
+    BindingAction(usize, Action),
+    BindingTouch(usize, TouchCondition),
+    BindingTouchesOnly(usize),
+    AddTouchBinding(TouchCondition),
+    BindingHold(usize, f64),
+    BindingWindow(usize, f64),
+    BindingGain(usize, f32),
+    RemoveBinding(usize),
+    Sounds(bool),
+    SoundVolume(f32),
+    HoldThreshold(f64),
+    TriggerPress(usize, f32),
+    TriggerRelease(usize, f32),
+    StickDeadzone(f32),
+    StickSector(f32),
+    SaveSettings,
 }
 
 impl Ready {
     pub fn update(&mut self, message: Message) -> Option<State> {
         match message {
-            Message::Controller(event) => match (event, self.mode) {
-                (ControllerEvent::Pressed, MicMode::PushToTalk) => {
-                    self.set_mute(false);
+            Message::Controller(event) => match event {
+                ControllerEvent::Pressed(index) => {
+                    if let Some(action) = self.action(index) {
+                        self.run(action, self.db(index), None);
+                    }
                 }
-                (ControllerEvent::Released, MicMode::PushToTalk) => {
-                    self.set_mute(true);
+                ControllerEvent::Released(index, held) => {
+                    if let Some(action) = self.action(index) {
+                        self.run(action, self.db(index), Some(held));
+                    }
                 }
-                (ControllerEvent::Pressed, MicMode::MuteAndUnmute) => {
-                    self.set_mute(!self.mic.muted);
+                ControllerEvent::BindingUpdate(index, binding) => {
+                    self.setting_binding = Some((index, binding));
                 }
-                (ControllerEvent::Released, MicMode::MuteAndUnmute) => {}
-                (ControllerEvent::BindingUpdate(binding), _) => {
-                    self.binding = binding;
+                ControllerEvent::BindingSet(index, binding) => {
+                    self.setting_binding = None;
+
+                    match index {
+                        0 => self.profile_mut().binding = binding,
+                        index => match self.profile_mut().bindings.get_mut(index - 1) {
+                            Some(action_binding) => action_binding.binding = binding,
+                            None => self.profile_mut().bindings.push(ActionBinding {
+                                binding,
+                                action: Action::ToggleMute,
+                                db: GAIN_STEP,
+                            }),
+                        },
+                    }
+
+                    self.save_settings();
                 }
-                (ControllerEvent::BindingSet(binding), _) => {
-                    self.binding = binding;
-                    self.is_setting_binding = false;
+                ControllerEvent::BindingCancelled(index) => {
+                    if matches!(self.setting_binding, Some((setting, _)) if setting == index) {
+                        self.setting_binding = None;
+                    }
                 }
             },
             Message::MuteToggle => {
-                let _ = unsafe { self.mic.set_mute(!self.mic.muted) };
+                self.mute(!self.muted());
             }
             Message::MicMode(mode) => {
                 let mute = match mode {
                     MicMode::MuteAndUnmute => false,
                     MicMode::PushToTalk => true,
+                    MicMode::PushToMute => false,
+                    MicMode::Hybrid => self.muted(),
                 };
 
-                let _ = unsafe { self.mic.set_mute(mute) };
+                self.mute(mute);
 
-                self.mode = mode;
+                self.profile_mut().mode = mode;
+                self.save_settings();
+            }
+            Message::MicSelected(choice) if choice == DEFAULT_MIC => {
+                self.settings.follow_default = true;
+                self.save_settings();
+                self.refresh_mics();
             }
             Message::MicSelected(choice) => {
-                let mics = match unsafe { microphone::active() } {
+                let mics = match self.backend.microphones() {
                     Ok(mics) => mics,
-                    Err(error) => {
-                        return Some(State::Error(Error {
-                            error: error.to_string(),
-                        }))
-                    }
+                    Err(error) => return Some(State::Error(error.into())),
                 };
 
                 match mics.iter().find(|mic| mic.name == choice).cloned() {
-                    Some(mic) => self.mic = mic,
+                    Some(mic) => self.select_mic(mic),
                     None => {
                         return Some(State::Error(Error {
                             error: "Mic now unable to be used".to_string(),
@@ -102,17 +270,218 @@ impl Ready {
                     }
                 }
             }
-            Message::SettingControllerBind => {
-                let _ = self.poller.try_send(poller::Message::SettingBind);
+            Message::MicGrouped(id, grouped) => {
+                self.settings.group.retain(|grouped| *grouped != id);
+
+                if grouped {
+                    self.settings.group.push(id);
+                }
+
+                self.save_settings();
+                self.refresh_group();
+
+                // A newly grouped mic starts out matching the rest
+                if self.group_mute() == GroupMute::Mixed {
+                    self.mute(self.mic.muted);
+                }
+            }
+            Message::Gain(db) => {
+                let _ = self.backend.set_gain(&self.mic.id, db);
+                self.refresh_levels();
+            }
+            Message::Volume(volume) => {
+                let _ = self.backend.set_volume(&self.mic.id, volume);
+                self.refresh_levels();
+            }
+            // A new default is followed when picked, and the fallback when the saved mic is missing
+            Message::Devices(
+                DeviceEvent::Added
+                | DeviceEvent::Removed
+                | DeviceEvent::StateChanged
+                | DeviceEvent::DefaultChanged,
+            ) => {
+                self.refresh_mics();
+            }
+            Message::MuteChanged(id, event) => {
+                let mic = iter::once(&mut self.mic)
+                    .chain(&mut self.group)
+                    .find(|mic| mic.id == id)?;
+
+                // Own changes are already applied, and stale ones from quick toggles are skipped
+                if event.ours != Some(true) && event.muted != mic.muted {
+                    mic.muted = event.muted;
+                    self.external_mute = true;
+                }
+            }
+            Message::DetectApps => {
+                let Ok(apps) = self.apps.apps() else {
+                    return None;
+                };
+
+                let detected = apps::detect(&apps, &self.settings.profiles);
+
+                // Only switches when the running app changes, so picking by hand still works
+                if detected != self.detected {
+                    self.detected = detected;
+                    self.switch_profile(detected.unwrap_or(self.settings.profile));
+                }
+            }
+            Message::ProfileSelected(index) => {
+                self.settings.profile = index;
+                self.save_settings();
+                self.switch_profile(index);
+            }
+            Message::AddProfile => {
+                let index = self.settings.profiles.len();
+
+                // Starts out as a copy of the current one
+                self.settings.profiles.push(Profile {
+                    name: format!("Profile {}", index + 1),
+                    apps: vec![],
+                    ..self.profile().clone()
+                });
+
+                self.settings.profile = index;
+                self.save_settings();
+                self.switch_profile(index);
+            }
+            Message::RemoveProfile => {
+                let index = self.profile;
+
+                if index == 0 {
+                    return None;
+                }
+
+                self.settings.profiles.remove(index);
+
+                if self.settings.profile == index {
+                    self.settings.profile = 0;
+                } else if self.settings.profile > index {
+                    self.settings.profile -= 1;
+                }
+
+                self.save_settings();
+
+                self.detected = None;
+                self.profile = self.settings.profile;
+                self.load_profile();
+            }
+            Message::ProfileName(name) => {
+                self.profile_mut().name = name;
+                self.save_settings();
+            }
+            Message::ProfileApps(apps) => {
+                self.profile_mut().apps = apps.split(',').map(str::to_string).collect();
+                self.save_settings();
+            }
+            Message::SettingControllerBind(index) => {
+                let _ = self.poller.try_send(poller::Message::SettingBind(index));
+
+                self.setting_binding = Some((index, Binding::empty()));
+            }
+            Message::CancelControllerBind => {
+                let _ = self.poller.try_send(poller::Message::CancelBind);
+
+                self.setting_binding = None;
+            }
+            Message::BindingAction(index, action) => {
+                if let Some(action_binding) = self.profile_mut().bindings.get_mut(index - 1) {
+                    action_binding.action = action;
+                    self.save_settings();
+                }
+            }
+            Message::BindingTouch(index, condition) => {
+                if let Some(binding) = self.profile_mut().binding_mut(index) {
+                    binding.toggle_touch(condition);
+                    self.save_settings();
+                    self.send_bindings();
+                }
+            }
+            Message::BindingTouchesOnly(index) => {
+                if let Some(binding) = self.profile_mut().binding_mut(index) {
+                    binding.keep_touches();
+                    self.save_settings();
+                    self.send_bindings();
+                }
+            }
+            // Touches can't be captured by pressing, so touch only bindings start out empty
+            Message::AddTouchBinding(condition) => {
+                let mut binding = Binding::empty();
+                binding.toggle_touch(condition);
+
+                self.profile_mut().bindings.push(ActionBinding {
+                    binding,
+                    action: Action::ToggleMute,
+                    db: GAIN_STEP,
+                });
+
+                self.save_settings();
+                self.send_bindings();
+            }
+            Message::BindingHold(index, hold) => {
+                if let Some(binding) = self.profile_mut().binding_mut(index) {
+                    binding.hold = hold;
+                    self.send_bindings();
+                }
+            }
+            Message::BindingWindow(index, window) => {
+                if let Some(binding) = self.profile_mut().binding_mut(index) {
+                    binding.window = window;
+                    self.send_bindings();
+                }
+            }
+            Message::BindingGain(index, db) => {
+                if let Some(action_binding) = self.profile_mut().bindings.get_mut(index - 1) {
+                    action_binding.db = db;
+                }
+            }
+            Message::RemoveBinding(index) => {
+                self.profile_mut().bindings.remove(index - 1);
+                self.save_settings();
+                self.send_bindings();
+            }
+            Message::Sounds(sounds) => {
+                self.settings.sounds = sounds;
+                self.save_settings();
+            }
+            Message::SoundVolume(volume) => {
+                self.settings.sound_volume = volume;
+            }
+            Message::HoldThreshold(threshold) => {
+                self.settings.hold_threshold = threshold;
+            }
+            Message::TriggerPress(trigger, press) => {
+                let threshold = &mut self.settings.trigger_thresholds[trigger];
 
-                self.is_setting_binding = true;
+                threshold.press = press;
+                threshold.release = threshold.release.min(press);
+
+                self.send_trigger_thresholds();
+            }
+            Message::TriggerRelease(trigger, release) => {
+                let threshold = &mut self.settings.trigger_thresholds[trigger];
+
+                threshold.release = release.min(threshold.press);
+
+                self.send_trigger_thresholds();
+            }
+            Message::StickDeadzone(deadzone) => {
+                self.settings.stick_deadzone = deadzone;
+                self.send_thumbsticks();
+            }
+            Message::StickSector(sector) => {
+                self.settings.stick_sector = sector;
+                self.send_thumbsticks();
+            }
+            Message::SaveSettings => {
+                self.save_settings();
             }
         };
 
         None
     }
 
-    pub fn view(&self) -> Element<Message> {
+    pub fn view(&self) -> Element<'_, Message> {
         let header = row![
             text("Microwave").width(Length::Fill).size(24),
             text!("Connected to {}", self.headset)
@@ -121,30 +490,118 @@ impl Ready {
         ]
         .align_y(Vertical::Center);
 
+        let idle = self.setting_binding.is_none();
+
+        let profiles: Vec<_> = self
+            .settings
+            .profiles
+            .iter()
+            .enumerate()
+            .map(|(index, profile)| ProfileOption(index, profile.name.clone()))
+            .collect();
+
+        let selected = profiles.get(self.profile).cloned();
+
+        let profile = column![
+            row![
+                text("Profile").width(Length::Fill),
+                text(if self.detected == Some(self.profile) {
+                    "Switched to for a running app"
+                } else {
+                    ""
+                })
+            ],
+            row![
+                pick_list(profiles, selected, |ProfileOption(index, _)| {
+                    Message::ProfileSelected(index)
+                })
+                .width(Length::Fill)
+                .padding(16),
+                button("Add")
+                    .on_press_maybe(idle.then_some(Message::AddProfile))
+                    .style(button::secondary)
+                    .padding(16),
+                button("Remove")
+                    .on_press_maybe((idle && self.profile != 0).then_some(Message::RemoveProfile))
+                    .style(button::danger)
+                    .padding(16)
+            ]
+            .spacing(8)
+        ]
+        .push_maybe((self.profile != 0).then(|| {
+            text_input("Name", &self.profile().name)
+                .on_input(Message::ProfileName)
+                .padding(16)
+        }))
+        .push_maybe((self.profile != 0).then(|| {
+            text_input(
+                "Apps that switch to this profile, like VRChat.exe",
+                &self.profile().apps.join(","),
+            )
+            .on_input(Message::ProfileApps)
+            .padding(16)
+        }))
+        .spacing(8);
+
         let mic_mode = column![
             radio(
                 "Mute / Unmute",
                 MicMode::MuteAndUnmute,
-                Some(self.mode),
+                Some(self.profile().mode),
                 Message::MicMode,
             ),
             radio(
                 "Push To Talk",
                 MicMode::PushToTalk,
-                Some(self.mode),
+                Some(self.profile().mode),
+                Message::MicMode,
+            ),
+            radio(
+                "Push To Mute",
+                MicMode::PushToMute,
+                Some(self.profile().mode),
+                Message::MicMode,
+            ),
+            radio(
+                "Tap To Toggle / Hold To Talk",
+                MicMode::Hybrid,
+                Some(self.profile().mode),
                 Message::MicMode,
             )
         ]
+        .push_maybe((self.profile().mode == MicMode::Hybrid).then(|| {
+            row![
+                text!("Hold for {:.2}s to talk", self.settings.hold_threshold).width(Length::Fill),
+                slider(
+                    0.1..=1.5,
+                    self.settings.hold_threshold,
+                    Message::HoldThreshold
+                )
+                .step(0.05)
+                .on_release(Message::SaveSettings)
+                .width(160)
+            ]
+            .align_y(Vertical::Center)
+        }))
         .spacing(8);
 
+        let group_mute = self.group_mute();
+
         let mic_toggle = button(
             row![
-                text(if self.mic.muted { "Unmute" } else { "Mute" }).width(Length::Fill),
-                svg(svg::Handle::from_memory(if self.mic.muted {
-                    MUTED_SVG
-                } else {
-                    UNMUTED_SVG
-                }))
+                text(match group_mute {
+                    GroupMute::Muted => "Unmute",
+                    GroupMute::Live => "Mute",
+                    GroupMute::Mixed => "Mute All",
+                })
+                .width(Length::Fill),
+                svg(svg::Handle::from_memory(
+                    if group_mute == GroupMute::Muted {
+                        MUTED_SVG
+                    } else {
+                        UNMUTED_SVG
+                    }
+                ))
                 .width(40),
             ]
             .align_y(Vertical::Center),
@@ -152,48 +609,676 @@ impl Ready {
         .width(Length::Fill)
         .padding(16)
         .style(button::secondary)
-        .on_press_maybe((self.mode == MicMode::MuteAndUnmute).then_some(Message::MuteToggle));
+        .on_press_maybe(
+            matches!(
+                self.profile().mode,
+                MicMode::MuteAndUnmute | MicMode::Hybrid
+            )
+            .then_some(Message::MuteToggle),
+        );
+
+        let mic_toggle = column![mic_toggle]
+            .push_maybe((!self.group.is_empty()).then(|| {
+                text!(
+                    "{} mics, {}",
+                    self.group.len() + 1,
+                    match group_mute {
+                        GroupMute::Muted => "all muted",
+                        GroupMute::Live => "all live",
+                        GroupMute::Mixed => "some muted",
+                    }
+                )
+                .size(14)
+            }))
+            .push_maybe(self.external_mute.then(|| {
+                text(match group_mute {
+                    GroupMute::Muted => "Muted outside of Microwave",
+                    GroupMute::Live => "Unmuted outside of Microwave",
+                    GroupMute::Mixed => "Changed outside of Microwave",
+                })
+                .size(14)
+            }))
+            .extend(
+                self.mute_errors
+                    .iter()
+                    .map(|error| text(error).size(14).style(text::danger).into()),
+            )
+            .spacing(4);
+
+        let conflicts = controller::conflicts(&self.profile().controller_bindings());
 
         let controller_binding = column![
             text("Controller Binding"),
             row![
-                container(text(&self.binding))
+                container(text(self.binding_text(0)))
                     .style(container::bordered_box)
                     .width(Length::Fill)
                     .padding(16),
-                button("Set Bind")
-                    .on_press_maybe(
-                        (!self.is_setting_binding).then_some(Message::SettingControllerBind)
-                    )
-                    .padding(16)
+                self.bind_button(0)
             ]
-            .spacing(8)
+            .spacing(8),
+            self.binding_options(0, &self.profile().binding, &conflicts)
         ]
         .spacing(8);
 
+        let actions = self
+            .profile()
+            .bindings
+            .iter()
+            .enumerate()
+            .map(|(i, action_binding)| {
+                let index = i + 1;
+
+                column![
+                    row![
+                        container(text(self.binding_text(index)))
+                            .style(container::bordered_box)
+                            .width(Length::Fill)
+                            .padding(16),
+                        self.bind_button(index)
+                    ]
+                    .spacing(8),
+                    self.binding_options(index, &action_binding.binding, &conflicts),
+                    row![
+                        pick_list(Action::ALL, Some(action_binding.action), move |action| {
+                            Message::BindingAction(index, action)
+                        })
+                        .width(Length::Fill)
+                        .padding(16),
+                        button("Remove")
+                            .on_press_maybe(idle.then_some(Message::RemoveBinding(index)))
+                            .style(button::danger)
+                            .padding(16)
+                    ]
+                    .spacing(8)
+                ]
+                .push_maybe(Self::gain_options(index, action_binding))
+                .spacing(8)
+                .into()
+            });
+
+        let thumbsticks = row![
+            text!("Stick past {:.2}", self.settings.stick_deadzone).width(Length::Fill),
+            slider(
+                0.1..=1.0,
+                self.settings.stick_deadzone,
+                Message::StickDeadzone
+            )
+            .step(0.05)
+            .on_release(Message::SaveSettings)
+            .width(120),
+            text!("Sectors {:.0}°", self.settings.stick_sector)
+                .width(Length::Fill)
+                .align_x(Horizontal::Right),
+            slider(
+                30.0..=180.0,
+                self.settings.stick_sector,
+                Message::StickSector
+            )
+            .step(5.0)
+            .on_release(Message::SaveSettings)
+            .width(120)
+        ]
+        .spacing(8)
+        .align_y(Vertical::Center);
+
+        let triggers = TRIGGERS
+            .iter()
+            .zip(self.settings.trigger_thresholds)
+            .enumerate()
+            .map(|(trigger, (name, threshold))| {
+                column![
+                    text(*name),
+                    row![
+                        text!("Press {:.2}", threshold.press).width(Length::Fill),
+                        slider(0.05..=1.0, threshold.press, move |press| {
+                            Message::TriggerPress(trigger, press)
+                        })
+                        .step(0.05)
+                        .on_release(Message::SaveSettings)
+                        .width(120),
+                        text!("Release {:.2}", threshold.release)
+                            .width(Length::Fill)
+                            .align_x(Horizontal::Right),
+                        slider(0.0..=1.0, threshold.release, move |release| {
+                            Message::TriggerRelease(trigger, release)
+                        })
+                        .step(0.05)
+                        .on_release(Message::SaveSettings)
+                        .width(120)
+                    ]
+                    .spacing(8)
+                    .align_y(Vertical::Center)
+                ]
+                .spacing(4)
+                .into()
+            });
+
+        let triggers = column![text("Trigger Thresholds")]
+            .extend(triggers)
+            .spacing(8);
+
+        let new_index = self.profile().bindings.len() + 1;
+
+        let new_action: Element<_> = match self.setting_binding {
+            Some((index, _)) if index == new_index => row![
+                container(text(self.binding_text(new_index)))
+                    .style(container::bordered_box)
+                    .width(Length::Fill)
+                    .padding(16),
+                self.bind_button(new_index)
+            ]
+            .spacing(8)
+            .into(),
+            _ => row![
+                button("Add Action Binding")
+                    .on_press_maybe(idle.then_some(Message::SettingControllerBind(new_index)))
+                    .style(button::secondary)
+                    .width(Length::Fill)
+                    .padding(16),
+                pick_list(
+                    TouchCondition::all(),
+                    None::<TouchCondition>,
+                    Message::AddTouchBinding
+                )
+                .placeholder("Add Touch Binding")
+                .width(Length::Fill)
+                .padding(16)
+            ]
+            .spacing(8)
+            .into(),
+        };
+
+        let actions = column![text("Action Bindings")]
+            .extend(actions)
+            .push(new_action)
+            .push(thumbsticks)
+            .spacing(8);
+
+        let options: Vec<String> = std::iter::once(DEFAULT_MIC.to_string())
+            .chain(self.mics.iter().map(|mic| mic.name.clone()))
+            .collect();
+
+        let selected = if self.settings.follow_default {
+            DEFAULT_MIC.to_string()
+        } else {
+            self.mic.name.clone()
+        };
+
         let mics = column![
             text("Microphone"),
-            pick_list(
-                self.mics.as_slice(),
-                Some(&self.mic.name),
-                Message::MicSelected,
-            )
-            .width(Length::Fill)
-            .padding(16)
+            pick_list(options, Some(selected), Message::MicSelected)
+                .width(Length::Fill)
+                .padding(16)
         ]
+        .push_maybe(
+            self.settings
+                .follow_default
+                .then(|| text!("Using {}", self.mic.name).size(14)),
+        )
+        // Devices without a dB range still get their volume
+        .push_maybe(
+            match (self.gain, self.volume) {
+                (Some(gain), _) => Some(row![
+                    text!("Gain {:+.1} dB", gain.db).width(Length::Fill),
+                    slider(gain.min..=gain.max, gain.db, Message::Gain)
+                        .step(0.5)
+                        .width(240)
+                ]),
+                (None, Some(volume)) => Some(row![
+                    text!("Volume {:.0}%", volume * 100.0).width(Length::Fill),
+                    slider(0.0..=1.0, volume, Message::Volume)
+                        .step(0.01)
+                        .width(240)
+                ]),
+                (None, None) => None,
+            }
+            .map(|row| row.align_y(Vertical::Center)),
+        )
+        .push_maybe((self.mics.len() > 1).then(|| text("Mute Together").size(14)))
+        .extend(
+            self.mics
+                .iter()
+                .filter(|mic| mic.id != self.mic.id)
+                .map(|mic| {
+                    let id = mic.id.clone();
+
+                    checkbox(&mic.name, self.settings.group.contains(&mic.id))
+                        .on_toggle(move |grouped| Message::MicGrouped(id.clone(), grouped))
+                        .into()
+                }),
+        )
         .spacing(8);
 
-        let column = column![header, mic_mode, mic_toggle, controller_binding, mics].spacing(20);
+        let sounds = row![
+            checkbox("Sound Feedback", self.settings.sounds)
+                .on_toggle(Message::Sounds)
+                .width(Length::Fill),
+            slider(0.0..=1.0, self.settings.sound_volume, Message::SoundVolume)
+                .step(0.05)
+                .on_release(Message::SaveSettings)
+                .width(160)
+        ]
+        .align_y(Vertical::Center);
 
-        container(column)
+        let problems = (!self.settings.problems.is_empty()).then(|| {
+            column![text("Settings Problems")]
+                .extend(
+                    self.settings
+                        .problems
+                        .iter()
+                        .map(|problem| text(problem).style(text::danger).into()),
+                )
+                .push(
+                    text(
+                        "Changes aren't saved until settings.toml is fixed and Microwave restarted",
+                    )
+                    .size(14),
+                )
+                .spacing(8)
+        });
+
+        let column = column![header]
+            .push_maybe(problems)
+            .push(profile)
+            .push(mic_mode)
+            .push(mic_toggle)
+            .push(controller_binding)
+            .push(actions)
+            .push(triggers)
+            .push(mics)
+            .push(sounds)
+            .spacing(20);
+
+        scrollable(container(column).padding([36, 16]))
             .width(Length::Fill)
             .height(Length::Fill)
-            .padding([36, 16])
             .into()
     }
 
+    fn binding_options(
+        &self,
+        index: usize,
+        binding: &Binding,
+        conflicts: &[Conflict],
+    ) -> Element<'_, Message> {
+        let touch = pick_list(
+            TouchCondition::all(),
+            None::<TouchCondition>,
+            move |condition| Message::BindingTouch(index, condition),
+        )
+        .placeholder("Toggle Touch Condition")
+        .width(Length::Fill)
+        .padding(16);
+
+        let mut touches = binding.clone();
+        touches.keep_touches();
+
+        let idle = self.setting_binding.is_none();
+
+        // Lets the pressed inputs be taken off again, touches are only added by toggling
+        let touches_only = button("Touches Only")
+            .on_press_maybe(
+                (idle && binding.has_touches() && touches != *binding)
+                    .then_some(Message::BindingTouchesOnly(index)),
+            )
+            .style(button::secondary)
+            .padding(16);
+
+        let touch = row![touch, touches_only].spacing(8);
+
+        let timing = row![
+            text!("Hold {:.1}s", binding.hold).width(Length::Fill),
+            slider(0.0..=2.0, binding.hold, move |hold| {
+                Message::BindingHold(index, hold)
+            })
+            .step(0.1)
+            .on_release(Message::SaveSettings)
+            .width(120)
+        ]
+        .push_maybe(binding.is_sequence().then(|| {
+            text!("Gap {:.1}s", binding.window)
+                .width(Length::Fill)
+                .align_x(Horizontal::Right)
+        }))
+        .push_maybe(binding.is_sequence().then(|| {
+            slider(0.2..=1.0, binding.window, move |window| {
+                Message::BindingWindow(index, window)
+            })
+            .step(0.1)
+            .on_release(Message::SaveSettings)
+            .width(120)
+        }))
+        .spacing(8)
+        .align_y(Vertical::Center);
+
+        let warnings = conflicts
+            .iter()
+            .filter(|conflict| conflict.index == index)
+            .map(|conflict| {
+                text(self.conflict_text(conflict))
+                    .style(text::danger)
+                    .into()
+            });
+
+        column![touch, timing].extend(warnings).spacing(8).into()
+    }
+
+    // How far gain up and down step, or what gain is set to
+    fn gain_options(index: usize, action_binding: &ActionBinding) -> Option<Element<'_, Message>> {
+        let (label, range) = match action_binding.action {
+            Action::GainUp | Action::GainDown => {
+                (format!("Step {:.1} dB", action_binding.db), 0.5..=12.0)
+            }
+            Action::GainPreset => (format!("Gain {:+.1} dB", action_binding.db), -40.0..=20.0),
+            _ => return None,
+        };
+
+        Some(
+            row![
+                text(label).width(Length::Fill),
+                slider(range, action_binding.db, move |db| {
+                    Message::BindingGain(index, db)
+                })
+                .step(0.5)
+                .on_release(Message::SaveSettings)
+                .width(240)
+            ]
+            .align_y(Vertical::Center)
+            .into(),
+        )
+    }
+
+    fn conflict_text(&self, conflict: &Conflict) -> String {
+        let other = match (conflict.other, self.action(conflict.other)) {
+            (0, _) | (_, None) => "the controller binding".to_string(),
+            (_, Some(action)) => format!("the {action} binding"),
+        };
+
+        match conflict.kind {
+            ConflictKind::Same => format!("Same as {other}, never fires"),
+            ConflictKind::Subsumed => format!("Part of {other}, held back while it's pressed"),
+            ConflictKind::Overlap => format!("Overlaps {other}, both fire when held together"),
+            ConflictKind::Tap => format!("Held shorter than {other}, fires when let go"),
+        }
+    }
+
+    // Turns into a cancel button while this binding is being set
+    fn bind_button(&self, index: usize) -> Element<'_, Message> {
+        match self.setting_binding {
+            Some((setting, _)) if setting == index => button("Cancel")
+                .on_press(Message::CancelControllerBind)
+                .style(button::secondary)
+                .padding(16)
+                .into(),
+            ref setting => button("Set Bind")
+                .on_press_maybe(
+                    setting
+                        .is_none()
+                        .then_some(Message::SettingControllerBind(index)),
+                )
+                .padding(16)
+                .into(),
+        }
+    }
+
+    fn profile(&self) -> &Profile {
+        &self.settings.profiles[self.profile]
+    }
+
+    fn profile_mut(&mut self) -> &mut Profile {
+        &mut self.settings.profiles[self.profile]
+    }
+
+    fn switch_profile(&mut self, index: usize) {
+        if index != self.profile {
+            self.profile = index;
+            self.load_profile();
+        }
+    }
+
+    // Capture indices belong to the previous profile's bindings, so capturing stops
+    fn load_profile(&mut self) {
+        if self.setting_binding.take().is_some() {
+            let _ = self.poller.try_send(poller::Message::CancelBind);
+        }
+
+        self.release_mute();
+        self.send_bindings();
+    }
+
+    // Push modes start out as if their binding is released, the others keep the mic as is
+    pub fn release_mute(&mut self) {
+        let released = match self.profile().mode {
+            MicMode::MuteAndUnmute | MicMode::Hybrid => None,
+            MicMode::PushToTalk => Some(true),
+            MicMode::PushToMute => Some(false),
+        };
+
+        if let Some(mute) = released {
+            self.mute(mute);
+        }
+    }
+
+    fn binding_text(&self, index: usize) -> String {
+        match &self.setting_binding {
+            Some((setting, binding)) if *setting == index => binding.to_string(),
+            _ => match index {
+                0 => self.profile().binding.to_string(),
+                index => self.profile().bindings[index - 1].binding.to_string(),
+            },
+        }
+    }
+
+    fn action(&self, index: usize) -> Option<Action> {
+        match index {
+            0 => Some(self.profile().mode.action()),
+            index => self
+                .profile()
+                .bindings
+                .get(index - 1)
+                .map(|binding| binding.action),
+        }
+    }
+
+    // Gain step or preset of a binding, the controller binding only drives the mic mode
+    fn db(&self, index: usize) -> f32 {
+        match index {
+            0 => GAIN_STEP,
+            index => self
+                .profile()
+                .bindings
+                .get(index - 1)
+                .map_or(GAIN_STEP, |binding| binding.db),
+        }
+    }
+
+    // `held` is None on press and how long the binding was held for on release
+    fn run(&mut self, action: Action, db: f32, held: Option<f64>) {
+        match (action, held) {
+            (Action::ToggleMute, None) => self.set_mute(!self.muted()),
+            (Action::PushToTalk, held) => self.set_mute(held.is_some()),
+            (Action::PushToMute, held) => self.set_mute(held.is_none()),
+            // Unmuting right away keeps push to talk responsive, whether it was a tap is only
+            // known on release
+            (Action::Hybrid, None) => {
+                self.hybrid_muted = self.muted();
+
+                if self.group_mute() != GroupMute::Live {
+                    self.set_mute(false);
+                }
+            }
+            (Action::Hybrid, Some(held)) => {
+                let mute = if held < self.settings.hold_threshold {
+                    !self.hybrid_muted
+                } else {
+                    self.hybrid_muted
+                };
+
+                if mute != self.muted() || self.group_mute() == GroupMute::Mixed {
+                    self.set_mute(mute);
+                }
+            }
+            (Action::GainUp, None) => self.step_gain(db),
+            (Action::GainDown, None) => self.step_gain(-db),
+            (Action::GainPreset, None) => self.set_gain(db),
+            (Action::SwitchMicrophone, None) => {
+                let Ok(mics) = self.backend.microphones() else {
+                    return;
+                };
+
+                let next = mics
+                    .iter()
+                    .position(|mic| mic.id == self.mic.id)
+                    .map_or(0, |i| (i + 1) % mics.len());
+
+                if let Some(mic) = mics.get(next).cloned() {
+                    self.mics = mics;
+                    self.select_mic(mic);
+                }
+            }
+            // Lets the current state be checked without looking
+            (Action::PlaySound, None) => self.play_sound(self.muted()),
+            (_, Some(_)) => {}
+        }
+    }
+
+    fn select_mic(&mut self, mic: Microphone) {
+        self.settings.microphone = Some(mic.id.clone());
+        self.settings.follow_default = false;
+        self.save_settings();
+
+        self.mic = mic;
+        self.external_mute = false;
+        self.refresh_group();
+        self.refresh_levels();
+    }
+
+    // Keeps the current mic while it's plugged in, and goes back to the saved one once it returns
+    fn refresh_mics(&mut self) {
+        let (Ok(mics), Ok(default), Ok(communications)) = (
+            self.backend.microphones(),
+            self.backend.default_microphone(Role::Console),
+            self.backend.default_microphone(Role::Communications),
+        ) else {
+            return;
+        };
+
+        let saved = if self.settings.follow_default {
+            communications.as_deref()
+        } else {
+            self.settings.microphone.as_deref()
+        };
+
+        let preferred: Vec<_> = [saved, Some(&self.mic.id)].into_iter().flatten().collect();
+
+        let picked = audio::pick_microphone(&mics, &preferred, default.as_deref());
+
+        self.mics = mics;
+
+        let Some(mic) = picked else {
+            return;
+        };
+
+        let group: Vec<_> = self.group.iter().map(|mic| mic.id.clone()).collect();
+
+        // Mute changes of the same mic are left to `Message::MuteChanged`
+        if mic.id == self.mic.id {
+            self.mic.name = mic.name;
+            self.refresh_group();
+
+            if self.group.iter().map(|mic| &mic.id).ne(&group) {
+                self.release_mute();
+            }
+        } else {
+            self.mic = mic;
+            self.external_mute = false;
+            self.refresh_group();
+            self.refresh_levels();
+            self.release_mute();
+        }
+    }
+
+    // Grouped mics that are plugged in, other than the selected one
+    pub fn refresh_group(&mut self) {
+        self.group = self
+            .mics
+            .iter()
+            .filter(|mic| mic.id != self.mic.id && self.settings.group.contains(&mic.id))
+            .cloned()
+            .collect();
+    }
+
+    fn group_mute(&self) -> GroupMute {
+        let mut mics = iter::once(&self.mic).chain(&self.group);
+
+        if mics.clone().all(|mic| mic.muted) {
+            GroupMute::Muted
+        } else if mics.all(|mic| !mic.muted) {
+            GroupMute::Live
+        } else {
+            GroupMute::Mixed
+        }
+    }
+
+    // Only when every mic in the group is muted
+    fn muted(&self) -> bool {
+        self.group_mute() == GroupMute::Muted
+    }
+
+    pub fn refresh_levels(&mut self) {
+        self.gain = self.backend.gain(&self.mic.id).ok();
+        self.volume = self.backend.volume(&self.mic.id).ok();
+    }
+
+    // Read again first in case it was changed outside of Microwave
+    fn step_gain(&mut self, step: f32) {
+        if let Ok(gain) = self.backend.gain(&self.mic.id) {
+            self.set_gain(gain.db + step);
+        }
+    }
+
+    fn set_gain(&mut self, db: f32) {
+        if self.backend.set_gain(&self.mic.id, db).is_ok() {
+            self.refresh_levels();
+
+            if let Some(gain) = self.gain {
+                self.play_gain(gain);
+            }
+        }
+    }
+
+    // Goes through every mic in the group even if some fail, returns whether all of them worked
+    fn mute(&mut self, mute: bool) -> bool {
+        self.mute_errors.clear();
+
+        for mic in iter::once(&mut self.mic).chain(&mut self.group) {
+            let result = self
+                .backend
+                .set_mute(&mic.id, mute)
+                .and_then(|()| self.backend.muted(&mic.id));
+
+            match result {
+                Ok(muted) => mic.muted = muted,
+                Err(AudioError { code, reason }) => self
+                    .mute_errors
+                    .push(format!("{}: {reason} (code {code})", mic.name)),
+            }
+        }
+
+        self.external_mute = false;
+
+        self.mute_errors.is_empty()
+    }
+
     fn set_mute(&mut self, mute: bool) {
-        if unsafe { self.mic.set_mute(mute).is_ok() } {
+        if self.mute(mute) {
+            self.play_sound(mute);
+        }
+    }
+
+    fn play_sound(&self, mute: bool) {
+        if self.settings.sounds {
             if let Some((_, stream_handle)) = &self.audio {
                 let cursor = if mute { MUTED_AUDIO } else { UNMUTED_AUDIO };
 
@@ -202,8 +1287,52 @@ impl Ready {
                 };
 
                 // TODO: Try choosing a new default output if this errors
-                let _ = stream_handle.play_raw(source.convert_samples());
+                let _ = stream_handle
+                    .play_raw(source.convert_samples().amplify(self.settings.sound_volume));
+            }
+        }
+    }
+
+    // Higher pitch for higher gain, so the level can be told apart without looking
+    fn play_gain(&self, gain: Gain) {
+        if self.settings.sounds {
+            if let Some((_, stream_handle)) = &self.audio {
+                let position = if gain.max > gain.min {
+                    (gain.db - gain.min) / (gain.max - gain.min)
+                } else {
+                    1.0
+                };
+
+                let tone = SineWave::new(GAIN_TONE * 2f32.powf(position))
+                    .take_duration(GAIN_TONE_LENGTH)
+                    // A full scale sine is much louder than the mute sounds
+                    .amplify(self.settings.sound_volume * 0.5);
+
+                let _ = stream_handle.play_raw(tone);
             }
         }
     }
+
+    fn send_bindings(&mut self) {
+        let _ = self.poller.try_send(poller::Message::Bindings(
+            self.profile().controller_bindings(),
+        ));
+    }
+
+    fn send_trigger_thresholds(&mut self) {
+        let _ = self.poller.try_send(poller::Message::TriggerThresholds(
+            self.settings.trigger_thresholds,
+        ));
+    }
+
+    fn send_thumbsticks(&mut self) {
+        let _ = self.poller.try_send(poller::Message::Thumbsticks(
+            self.settings.stick_deadzone,
+            self.settings.stick_sector,
+        ));
+    }
+
+    fn save_settings(&self) {
+        let _ = self.settings.save();
+    }
 }
@@ -0,0 +1,346 @@
+use std::{fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    controller::{Binding, TriggerThreshold},
+    screen::ready::{Action, MicMode, GAIN_STEP},
+};
+
+const VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    pub version: u32,
+    // The first profile is the default one and can't be removed
+    pub profiles: Vec<Profile>,
+    // Profile picked by hand, used while no profile's app is running
+    pub profile: usize,
+    // Seconds before a hybrid press counts as a hold
+    pub hold_threshold: f64,
+    // Stable device id, names can change or repeat
+    pub microphone: Option<String>,
+    // Use whatever the system's default communications mic is instead
+    pub follow_default: bool,
+    // Other mics muted and unmuted along with the selected one, by id
+    pub group: Vec<String>,
+    // Left index, right index, left hand and right hand
+    pub trigger_thresholds: [TriggerThreshold; 4],
+    pub stick_deadzone: f32,
+    // Degrees each stick direction covers
+    pub stick_sector: f32,
+    pub sounds: bool,
+    pub sound_volume: f32,
+    // Problems found loading the file, which is then left as it is instead of being saved over
+    #[serde(skip)]
+    pub problems: Vec<String>,
+}
+
+// Mode and bindings used while one of `apps` is running
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Profile {
+    pub name: String,
+    // Executable names, such as `VRChat.exe`
+    pub apps: Vec<String>,
+    pub mode: MicMode,
+    // Drives the selected mic mode
+    pub binding: Binding,
+    pub bindings: Vec<ActionBinding>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionBinding {
+    pub binding: Binding,
+    pub action: Action,
+    // Step or preset for the gain actions
+    #[serde(default = "gain_step")]
+    pub db: f32,
+}
+
+fn gain_step() -> f32 {
+    GAIN_STEP
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            profiles: vec![Profile::default()],
+            profile: 0,
+            hold_threshold: 0.4,
+            microphone: None,
+            follow_default: false,
+            group: vec![],
+            trigger_thresholds: [TriggerThreshold::default(); 4],
+            stick_deadzone: 0.8,
+            stick_sector: 90.0,
+            sounds: true,
+            sound_volume: 1.0,
+            problems: vec![],
+        }
+    }
+}
+
+impl Default for Profile {
+    fn default() -> Self {
+        Self {
+            name: "Default".to_string(),
+            apps: vec![],
+            mode: MicMode::MuteAndUnmute,
+            binding: Binding::default(),
+            bindings: vec![],
+        }
+    }
+}
+
+impl Profile {
+    pub fn binding_mut(&mut self, index: usize) -> Option<&mut Binding> {
+        match index {
+            0 => Some(&mut self.binding),
+            index => self
+                .bindings
+                .get_mut(index - 1)
+                .map(|binding| &mut binding.binding),
+        }
+    }
+
+    // The mode binding comes first, matching the controller's binding indices
+    pub fn controller_bindings(&self) -> Vec<Binding> {
+        std::iter::once(self.binding.clone())
+            .chain(self.bindings.iter().map(|binding| binding.binding.clone()))
+            .collect()
+    }
+
+    pub fn matches(&self, app: &str) -> bool {
+        self.apps
+            .iter()
+            .map(|name| name.trim())
+            .any(|name| !name.is_empty() && name.eq_ignore_ascii_case(app))
+    }
+}
+
+impl Settings {
+    // Missing files fall back to the defaults. Whatever can't be read is reported and left out,
+    // and the file isn't saved over so nothing in it is lost
+    pub fn load() -> Self {
+        let Some(path) = path() else {
+            return Self::default();
+        };
+
+        let mut problems = vec![];
+
+        let file = match fs::read_to_string(path) {
+            Ok(file) => file,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(error) => {
+                problems.push(format!("Couldn't read settings.toml: {error}"));
+                String::new()
+            }
+        };
+
+        let table = file.parse::<toml::Table>().unwrap_or_else(|error| {
+            problems.push(format!("Invalid settings.toml: {}", error.message()));
+            toml::Table::new()
+        });
+
+        Self::from_table(table, problems)
+    }
+
+    // Everything after reading the file, `problems` has what already went wrong
+    fn from_table(table: toml::Table, mut problems: Vec<String>) -> Self {
+        let table = check_bindings(table, &mut problems);
+
+        let mut settings = table.try_into::<Self>().unwrap_or_else(|error| {
+            problems.push(format!("Invalid settings.toml: {}", error.message()));
+            Self::default()
+        });
+
+        // Unknown fields are skipped, so what this version knows about still loads
+        if settings.version > VERSION {
+            problems.push("settings.toml is from a newer version of Microwave".to_string());
+        }
+
+        settings.version = VERSION;
+        settings.problems = problems;
+
+        if settings.profiles.is_empty() {
+            settings.profiles.push(Profile::default());
+        }
+
+        if settings.profile >= settings.profiles.len() {
+            settings.profile = 0;
+        }
+
+        // The sliders keep release at or below press, hand edited files might not
+        for threshold in &mut settings.trigger_thresholds {
+            if !threshold.press.is_finite() || !threshold.release.is_finite() {
+                *threshold = TriggerThreshold::default();
+            }
+
+            threshold.press = threshold.press.clamp(0.05, 1.0);
+            threshold.release = threshold.release.clamp(0.0, threshold.press);
+        }
+
+        settings
+    }
+
+    pub fn save(&self) -> io::Result<()> {
+        if !self.problems.is_empty() {
+            return Err(io::Error::other("settings.toml had problems loading"));
+        }
+
+        let path = path().ok_or(io::Error::new(
+            io::ErrorKind::NotFound,
+            "No config directory",
+        ))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        fs::write(
+            path,
+            toml::to_string_pretty(self).map_err(io::Error::other)?,
+        )
+    }
+}
+
+// Bindings that don't parse are reported and left empty, the rest of the file still loads
+fn check_bindings(mut table: toml::Table, problems: &mut Vec<String>) -> toml::Table {
+    let Some(profiles) = table
+        .get_mut("profiles")
+        .and_then(toml::Value::as_array_mut)
+    else {
+        return table;
+    };
+
+    for profile in profiles.iter_mut().filter_map(toml::Value::as_table_mut) {
+        let name = profile
+            .get("name")
+            .and_then(toml::Value::as_str)
+            .unwrap_or("Default")
+            .to_string();
+
+        let mut check = |value: Option<&mut toml::Value>| {
+            let Some(value) = value else {
+                return;
+            };
+
+            if let Some(Err(error)) = value.as_str().map(str::parse::<Binding>) {
+                problems.push(format!(
+                    "Binding \"{}\" in {name} is disabled: {error}",
+                    value.as_str().unwrap_or_default()
+                ));
+
+                *value = toml::Value::String(String::new());
+            }
+        };
+
+        check(profile.get_mut("binding"));
+
+        if let Some(bindings) = profile
+            .get_mut("bindings")
+            .and_then(toml::Value::as_array_mut)
+        {
+            for binding in bindings {
+                check(binding.get_mut("binding"));
+            }
+        }
+    }
+
+    table
+}
+
+fn path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("Microwave").join("settings.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn load(text: &str) -> Settings {
+        Settings::from_table(text.parse().unwrap(), vec![])
+    }
+
+    #[test]
+    fn bad_binding_is_reported_and_disabled() {
+        let settings = load(
+            r#"
+            [[profiles]]
+            name = "Games"
+            binding = "LThumb+Typo"
+
+            [[profiles.bindings]]
+            binding = "A,A"
+            action = "PlaySound"
+            "#,
+        );
+
+        assert_eq!(
+            settings.problems,
+            ["Binding \"LThumb+Typo\" in Games is disabled: Unknown input \"Typo\""]
+        );
+        assert_eq!(settings.profiles[0].binding, Binding::empty());
+        // The rest of the profile still loads
+        assert_eq!(
+            settings.profiles[0].bindings[0].binding,
+            "A,A".parse().unwrap()
+        );
+        assert!(settings.save().is_err());
+    }
+
+    #[test]
+    fn profile_out_of_range() {
+        let settings = load("profile = 2\n[[profiles]]\n[[profiles]]\n");
+
+        assert_eq!(settings.profiles.len(), 2);
+        assert_eq!(settings.profile, 0);
+        assert!(settings.problems.is_empty());
+
+        let settings = load("profile = 1\nprofiles = []\n");
+
+        assert_eq!(settings.profiles, [Profile::default()]);
+        assert_eq!(settings.profile, 0);
+    }
+
+    #[test]
+    fn newer_version_disables_saving() {
+        let settings = load(&format!(
+            "version = {}\nhold_threshold = 0.6\n",
+            VERSION + 1
+        ));
+
+        assert_eq!(
+            settings.problems,
+            ["settings.toml is from a newer version of Microwave"]
+        );
+        assert_eq!(settings.hold_threshold, 0.6);
+        assert!(settings.save().is_err());
+    }
+
+    #[test]
+    fn trigger_thresholds_are_clamped() {
+        let settings = load(
+            r#"
+            trigger_thresholds = [
+                { press = 0.5, release = 0.9 },
+                { press = 2.0, release = -1.0 },
+                { press = nan, release = 0.5 },
+                { press = 0.0, release = 0.0 },
+            ]
+            "#,
+        );
+
+        let thresholds = settings
+            .trigger_thresholds
+            .map(|threshold| (threshold.press, threshold.release));
+
+        assert_eq!(
+            thresholds,
+            [(0.5, 0.5), (1.0, 0.0), (0.85, 0.75), (0.05, 0.0)]
+        );
+    }
+}